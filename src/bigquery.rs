@@ -0,0 +1,519 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::models::{ColumnInfo, Config, PartitionRange, Partitioning, TableInfo, TableMetadata};
+
+pub const DEFAULT_API_ENDPOINT: &str = "https://bigquery.googleapis.com/bigquery/v2";
+
+/// A field of a BigQuery table schema, as printed by `bq show --schema --format=prettyjson`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaField {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub field_type: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mode: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fields: Vec<SchemaField>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub policy_tags: Option<PolicyTags>,
+    /// Properties not modelled here (maxLength, precision, defaultValueExpression, ...), kept so
+    /// that a schema sent back with `tables.patch` doesn't drop them
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PolicyTags {
+    #[serde(default)]
+    pub names: Vec<String>,
+}
+
+/// `bq show --schema` prints a bare array, while the `tables.get` resource wraps it in `{"fields": [...]}`
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum SchemaDocument {
+    Fields(Vec<SchemaField>),
+    Wrapped { fields: Vec<SchemaField> },
+}
+
+/// A BigQuery table resource, as returned by `tables.get` or `bq show --format=prettyjson`.
+/// Int64 values are encoded as JSON strings by the API.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TableResource {
+    pub table_reference: TableReference,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub etag: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub labels: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schema: Option<TableSchema>,
+    #[serde(default, rename = "type", skip_serializing_if = "Option::is_none")]
+    pub table_type: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time_partitioning: Option<TimePartitioning>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub range_partitioning: Option<RangePartitioning>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub clustering: Option<Clustering>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expiration_time: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub view: Option<ViewDefinition>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub materialized_view: Option<ViewDefinition>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TableReference {
+    pub project_id: String,
+    pub dataset_id: String,
+    pub table_id: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TableSchema {
+    #[serde(default)]
+    pub fields: Vec<SchemaField>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimePartitioning {
+    #[serde(rename = "type")]
+    pub partition_type: String,
+    #[serde(default)]
+    pub field: Option<String>,
+    #[serde(default)]
+    pub expiration_ms: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RangePartitioning {
+    pub field: String,
+    pub range: ResourceRange,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResourceRange {
+    pub start: String,
+    pub end: String,
+    pub interval: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Clustering {
+    #[serde(default)]
+    pub fields: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ViewDefinition {
+    pub query: String,
+}
+
+fn parse_int64(value: &str, name: &str) -> Result<i64> {
+    value.parse()
+        .with_context(|| format!("Invalid {} in table resource: {}", name, value))
+}
+
+impl TableResource {
+    /// Convert the resource into local table metadata
+    pub fn into_metadata(self) -> Result<TableMetadata> {
+        let partitioning = if let Some(tp) = self.time_partitioning {
+            Some(Partitioning {
+                partition_type: tp.partition_type,
+                field: tp.field,
+                expiration_ms: tp.expiration_ms.as_deref()
+                    .map(|v| parse_int64(v, "timePartitioning.expirationMs"))
+                    .transpose()?,
+                range: None,
+            })
+        } else if let Some(rp) = self.range_partitioning {
+            Some(Partitioning {
+                partition_type: "RANGE".to_string(),
+                field: Some(rp.field),
+                expiration_ms: None,
+                range: Some(PartitionRange {
+                    start: parse_int64(&rp.range.start, "rangePartitioning.range.start")?,
+                    end: parse_int64(&rp.range.end, "rangePartitioning.range.end")?,
+                    interval: parse_int64(&rp.range.interval, "rangePartitioning.range.interval")?,
+                }),
+            })
+        } else {
+            None
+        };
+
+        let expiration_time = self.expiration_time.as_deref()
+            .map(|v| parse_int64(v, "expirationTime"))
+            .transpose()?;
+
+        Ok(TableMetadata {
+            table: TableInfo {
+                name: self.table_reference.table_id,
+                project_id: self.table_reference.project_id,
+                dataset_id: self.table_reference.dataset_id,
+                description: self.description.filter(|d| !d.is_empty()),
+                table_type: self.table_type,
+                labels: self.labels,
+                partitioning,
+                clustering: self.clustering.map(|c| c.fields).unwrap_or_default(),
+                expiration_time,
+                view_query: self.view.or(self.materialized_view).map(|v| v.query),
+            },
+            columns: self.schema.unwrap_or_default()
+                .fields.into_iter().map(ColumnInfo::from).collect(),
+        })
+    }
+}
+
+/// Parse a BigQuery table resource JSON document into table metadata
+pub fn parse_table_resource(content: &str) -> Result<TableMetadata> {
+    let resource: TableResource = serde_json::from_str(content)
+        .context("Failed to parse BigQuery table resource JSON")?;
+    resource.into_metadata()
+}
+
+impl From<SchemaField> for ColumnInfo {
+    fn from(field: SchemaField) -> Self {
+        Self {
+            name: field.name,
+            column_type: field.field_type,
+            description: field.description.filter(|d| !d.is_empty()),
+            mode: field.mode,
+            fields: field.fields.into_iter().map(ColumnInfo::from).collect(),
+            policy_tags: field.policy_tags.map(|t| t.names).unwrap_or_default(),
+            removed: false,
+        }
+    }
+}
+
+/// Parse BigQuery schema JSON into columns
+pub fn parse_schema(content: &str) -> Result<Vec<ColumnInfo>> {
+    let document: SchemaDocument = serde_json::from_str(content)
+        .context("Failed to parse BigQuery schema JSON")?;
+
+    let fields = match document {
+        SchemaDocument::Fields(fields) => fields,
+        SchemaDocument::Wrapped { fields } => fields,
+    };
+
+    Ok(fields.into_iter().map(ColumnInfo::from).collect())
+}
+
+/// Merge a BigQuery schema into existing local columns.
+///
+/// The schema decides column order, types, modes and policy tags. Local descriptions
+/// are kept where the schema has none, and columns only known locally are kept at the end.
+pub fn merge_columns(local: &[ColumnInfo], remote: Vec<ColumnInfo>) -> Vec<ColumnInfo> {
+    let mut merged: Vec<ColumnInfo> = remote.into_iter().map(|mut column| {
+        if let Some(existing) = local.iter().find(|c| c.name == column.name) {
+            if column.description.is_none() {
+                column.description = existing.description.clone();
+            }
+            column.fields = merge_columns(&existing.fields, column.fields);
+        }
+        column
+    }).collect();
+
+    for column in local {
+        if !merged.iter().any(|c| c.name == column.name) {
+            merged.push(column.clone());
+        }
+    }
+
+    merged
+}
+
+/// Minimal client for the BigQuery `tables` REST API
+pub struct BigQueryClient {
+    http: reqwest::Client,
+    endpoint: String,
+    access_token: Option<String>,
+}
+
+impl BigQueryClient {
+    pub fn new(endpoint: &str, access_token: Option<String>) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            endpoint: endpoint.trim_end_matches('/').to_string(),
+            access_token,
+        }
+    }
+
+    /// Create a client for the configured endpoint.
+    ///
+    /// The access token is read from `BQ_META_ACCESS_TOKEN`, falling back to
+    /// `gcloud auth print-access-token` when talking to the public endpoint.
+    pub fn from_config(config: &Config) -> Self {
+        let endpoint = config.api_endpoint.as_deref().unwrap_or(DEFAULT_API_ENDPOINT);
+        let access_token = std::env::var("BQ_META_ACCESS_TOKEN").ok().or_else(|| {
+            if endpoint != DEFAULT_API_ENDPOINT {
+                return None;
+            }
+            let output = std::process::Command::new("gcloud")
+                .args(["auth", "print-access-token"])
+                .output()
+                .ok()?;
+            output.status.success()
+                .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+        });
+        Self::new(endpoint, access_token)
+    }
+
+    fn table_url(&self, project_id: &str, dataset_id: &str, table_name: &str) -> String {
+        format!(
+            "{}/projects/{}/datasets/{}/tables/{}",
+            self.endpoint, project_id, dataset_id, table_name
+        )
+    }
+
+    /// Send a request, returning `None` on 404 and an error for other failures
+    async fn send<T: serde::de::DeserializeOwned>(&self, request: reqwest::RequestBuilder) -> Result<Option<T>> {
+        let request = match self.access_token {
+            Some(ref token) => request.bearer_auth(token),
+            None => request,
+        };
+        let response = request.send().await.context("BigQuery API request failed")?;
+        let status = response.status();
+        let body = response.text().await.context("Failed to read BigQuery API response")?;
+
+        if status == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if status == reqwest::StatusCode::PRECONDITION_FAILED {
+            return Err(anyhow::anyhow!("The table was modified in BigQuery since it was fetched; run the command again"));
+        }
+        if !status.is_success() {
+            let message = serde_json::from_str::<serde_json::Value>(&body).ok()
+                .and_then(|v| v["error"]["message"].as_str().map(str::to_string))
+                .unwrap_or(body);
+            return Err(anyhow::anyhow!("BigQuery API returned {}: {}", status, message));
+        }
+
+        serde_json::from_str(&body).map(Some).context("Failed to parse BigQuery API response")
+    }
+
+    /// Fetch a table resource (`tables.get`)
+    pub async fn get_table(&self, project_id: &str, dataset_id: &str, table_name: &str) -> Result<TableResource> {
+        self.find_table(project_id, dataset_id, table_name).await?
+            .ok_or_else(|| anyhow::anyhow!("Table not found in BigQuery: {}.{}.{}", project_id, dataset_id, table_name))
+    }
+
+    /// Fetch a table resource, returning `None` if the table does not exist
+    pub async fn find_table(&self, project_id: &str, dataset_id: &str, table_name: &str) -> Result<Option<TableResource>> {
+        let url = self.table_url(project_id, dataset_id, table_name);
+        self.send(self.http.get(url)).await
+    }
+
+    /// List the table names of a dataset (`tables.list`), following pagination
+    pub async fn list_tables(&self, project_id: &str, dataset_id: &str) -> Result<Vec<String>> {
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct TableList {
+            #[serde(default)]
+            tables: Vec<TableListEntry>,
+            next_page_token: Option<String>,
+        }
+
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct TableListEntry {
+            table_reference: TableReference,
+        }
+
+        let url = format!("{}/projects/{}/datasets/{}/tables", self.endpoint, project_id, dataset_id);
+        let mut names = Vec::new();
+        let mut page_token: Option<String> = None;
+
+        loop {
+            let mut request = self.http.get(&url);
+            if let Some(ref token) = page_token {
+                request = request.query(&[("pageToken", token)]);
+            }
+            let Some(page) = self.send::<TableList>(request).await? else {
+                return Err(anyhow::anyhow!("Dataset not found in BigQuery: {}.{}", project_id, dataset_id));
+            };
+            names.extend(page.tables.into_iter().map(|t| t.table_reference.table_id));
+            match page.next_page_token {
+                Some(token) => page_token = Some(token),
+                None => break,
+            }
+        }
+
+        names.sort();
+        Ok(names)
+    }
+
+    /// Update a table resource with the given partial body (`tables.patch`).
+    ///
+    /// With an `etag` the patch is only applied if the table hasn't changed since it was fetched.
+    pub async fn patch_table(&self, project_id: &str, dataset_id: &str, table_name: &str, patch: &serde_json::Value, etag: Option<&str>) -> Result<TableResource> {
+        let url = self.table_url(project_id, dataset_id, table_name);
+        let mut request = self.http.patch(url).json(patch);
+        if let Some(etag) = etag {
+            request = request.header(reqwest::header::IF_MATCH, etag);
+        }
+        self.send(request).await?
+            .ok_or_else(|| anyhow::anyhow!("Table not found in BigQuery: {}.{}.{}", project_id, dataset_id, table_name))
+    }
+}
+
+/// Merge remote table metadata into an existing local copy.
+///
+/// Remote table properties replace local ones, except that the local table description
+/// is kept when the remote has none. Columns are merged with [`merge_columns`].
+pub fn merge_table_metadata(local: &TableMetadata, remote: TableMetadata) -> TableMetadata {
+    let mut table = remote.table;
+    if table.description.is_none() {
+        table.description = local.table.description.clone();
+    }
+
+    TableMetadata {
+        table,
+        columns: merge_columns(&local.columns, remote.columns),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_merge_schema() {
+        let schema = r#"[
+            {"name": "user_id", "type": "STRING", "mode": "REQUIRED"},
+            {"name": "items", "type": "RECORD", "mode": "REPEATED", "description": "Purchased items",
+             "fields": [{"name": "item_id", "type": "STRING", "policyTags": {"names": ["projects/p/taxonomies/1/policyTags/2"]}}]}
+        ]"#;
+        let remote = parse_schema(schema).unwrap();
+        assert_eq!(remote[1].fields[0].policy_tags, vec!["projects/p/taxonomies/1/policyTags/2"]);
+
+        let wrapped = parse_schema(&format!(r#"{{"fields": {}}}"#, schema)).unwrap();
+        assert_eq!(wrapped.len(), 2);
+
+        let local = vec![
+            ColumnInfo {
+                name: "user_id".to_string(),
+                column_type: "INT64".to_string(),
+                description: Some("Local user id".to_string()),
+                ..Default::default()
+            },
+            ColumnInfo {
+                name: "legacy".to_string(),
+                column_type: "STRING".to_string(),
+                ..Default::default()
+            },
+        ];
+
+        let merged = merge_columns(&local, remote);
+        let names: Vec<&str> = merged.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["user_id", "items", "legacy"]);
+        assert_eq!(merged[0].column_type, "STRING");
+        assert_eq!(merged[0].description.as_deref(), Some("Local user id"));
+        assert_eq!(merged[1].description.as_deref(), Some("Purchased items"));
+    }
+
+    #[test]
+    fn test_parse_table_resource() {
+        let resource = r#"{
+            "kind": "bigquery#table",
+            "tableReference": {"projectId": "p", "datasetId": "d", "tableId": "events"},
+            "description": "Events",
+            "labels": {"team": "analytics"},
+            "type": "TABLE",
+            "schema": {"fields": [{"name": "ts", "type": "TIMESTAMP"}]},
+            "timePartitioning": {"type": "DAY", "field": "ts", "expirationMs": "5184000000"},
+            "clustering": {"fields": ["user_id"]},
+            "expirationTime": "1735689600000"
+        }"#;
+        let metadata = parse_table_resource(resource).unwrap();
+        assert_eq!(metadata.table.name, "events");
+        assert_eq!(metadata.table.labels["team"], "analytics");
+        assert_eq!(metadata.table.partitioning.as_ref().unwrap().expiration_ms, Some(5184000000));
+        assert_eq!(metadata.table.clustering, vec!["user_id"]);
+        assert_eq!(metadata.table.expiration_time, Some(1735689600000));
+        assert_eq!(metadata.columns[0].name, "ts");
+
+        let view = r#"{
+            "tableReference": {"projectId": "p", "datasetId": "d", "tableId": "v"},
+            "type": "VIEW",
+            "view": {"query": "SELECT 1", "useLegacySql": false},
+            "rangePartitioning": {"field": "n", "range": {"start": "0", "end": "100", "interval": "10"}}
+        }"#;
+        let metadata = parse_table_resource(view).unwrap();
+        assert_eq!(metadata.table.view_query.as_deref(), Some("SELECT 1"));
+        assert_eq!(metadata.table.partitioning.unwrap().range.unwrap().interval, 10);
+    }
+
+    /// Serve a single canned HTTP response and return the request head that was received
+    async fn serve_once(status: &'static str, body: &'static str) -> (String, tokio::task::JoinHandle<String>) {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let endpoint = format!("http://{}/bigquery/v2", listener.local_addr().unwrap());
+        let handle = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut buf = vec![0u8; 8192];
+            let n = stream.read(&mut buf).await.unwrap();
+            let response = format!(
+                "HTTP/1.1 {}\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                status, body.len(), body
+            );
+            stream.write_all(response.as_bytes()).await.unwrap();
+            String::from_utf8_lossy(&buf[..n]).to_string()
+        });
+        (endpoint, handle)
+    }
+
+    #[tokio::test]
+    async fn test_client_against_local_endpoint() {
+        let (endpoint, handle) = serve_once(
+            "200 OK",
+            r#"{"tableReference": {"projectId": "p", "datasetId": "d", "tableId": "t"}, "description": "remote"}"#,
+        ).await;
+        let client = BigQueryClient::new(&endpoint, None);
+        let resource = client.get_table("p", "d", "t").await.unwrap();
+        assert_eq!(resource.description.as_deref(), Some("remote"));
+        assert_eq!(handle.await.unwrap().lines().next(), Some("GET /bigquery/v2/projects/p/datasets/d/tables/t HTTP/1.1"));
+
+        let (endpoint, handle) = serve_once(
+            "200 OK",
+            r#"{"tableReference": {"projectId": "p", "datasetId": "d", "tableId": "t"}}"#,
+        ).await;
+        let client = BigQueryClient::new(&endpoint, None);
+        client.patch_table("p", "d", "t", &serde_json::json!({"description": "x"}), Some("\"abc\"")).await.unwrap();
+        assert!(handle.await.unwrap().lines().any(|line| line.eq_ignore_ascii_case("if-match: \"abc\"")));
+
+        let (endpoint, _handle) = serve_once(
+            "412 Precondition Failed",
+            r#"{"error": {"code": 412, "message": "Precondition check failed."}}"#,
+        ).await;
+        let client = BigQueryClient::new(&endpoint, None);
+        let err = client.patch_table("p", "d", "t", &serde_json::json!({}), Some("\"abc\"")).await.unwrap_err();
+        assert!(err.to_string().contains("modified in BigQuery"));
+
+        let (endpoint, _handle) = serve_once(
+            "404 Not Found",
+            r#"{"error": {"code": 404, "message": "Not found: Table p:d.t"}}"#,
+        ).await;
+        let client = BigQueryClient::new(&endpoint, None);
+        assert!(client.find_table("p", "d", "t").await.unwrap().is_none());
+
+        let (endpoint, _handle) = serve_once(
+            "403 Forbidden",
+            r#"{"error": {"code": 403, "message": "Access Denied: Table p:d.t"}}"#,
+        ).await;
+        let client = BigQueryClient::new(&endpoint, None);
+        let err = client.get_table("p", "d", "t").await.unwrap_err();
+        assert!(err.to_string().contains("Access Denied: Table p:d.t"));
+    }
+}
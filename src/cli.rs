@@ -16,6 +16,9 @@ pub enum Commands {
         /// Custom path for metadata directory
         #[arg(short, long)]
         path: Option<String>,
+        /// Track changes in a git repository inside the data directory
+        #[arg(long)]
+        git: bool,
     },
     /// List tables
     List {
@@ -51,12 +54,24 @@ pub enum Commands {
         /// Case sensitive search
         #[arg(long)]
         case_sensitive: bool,
+        /// Tolerate typos by matching approximately (edit distance)
+        #[arg(long, conflicts_with = "regex")]
+        fuzzy: bool,
+        /// Minimum similarity (0.0-1.0) for fuzzy matches
+        #[arg(long, default_value_t = 0.8, requires = "fuzzy")]
+        threshold: f64,
+        /// Treat PATTERN as a query, e.g. 'column:user_id type:INT64 desc:billing -dataset:tmp'
+        #[arg(short, long, conflicts_with_all = ["all", "desc", "column", "col-desc", "regex", "fuzzy"])]
+        query: bool,
         /// Project ID to filter
         #[arg(short, long)]
         project: Option<String>,
         /// Dataset ID to filter
         #[arg(short, long)]
         dataset: Option<String>,
+        /// Maximum number of results to show (best matches first)
+        #[arg(short = 'n', long)]
+        limit: Option<usize>,
         /// Output format
         #[arg(short, long, default_value = "table")]
         output: String,
@@ -89,7 +104,7 @@ pub enum Commands {
     Edit {
         /// Table specification (project.dataset.table)
         table: String,
-        /// Column name
+        /// Column name (use dotted path for nested fields, e.g. record.field)
         column: String,
         /// New description
         #[arg(short, long)]
@@ -108,11 +123,207 @@ pub enum Commands {
     },
     /// Import table metadata
     Import {
-        /// Input file path
+        /// Input file path (or directory of JSON files with --resource)
         file: String,
         /// Force overwrite existing metadata
         #[arg(long)]
         force: bool,
+        /// Treat the input as `bq show --schema` JSON for this table (project.dataset.table)
+        #[arg(long, conflicts_with = "resource")]
+        schema: Option<String>,
+        /// Treat the input as `bq show --format=prettyjson` table resource JSON
+        #[arg(long)]
+        resource: bool,
+    },
+    /// Generate BigQuery DDL applying table and column descriptions
+    Ddl {
+        /// Table specification (project.dataset.table); all tables if omitted
+        table: Option<String>,
+        /// Project ID to filter
+        #[arg(short, long, conflicts_with = "table")]
+        project: Option<String>,
+        /// Dataset ID to filter
+        #[arg(short, long, conflicts_with = "table")]
+        dataset: Option<String>,
+        /// Output file path
+        #[arg(short, long)]
+        file: Option<String>,
+    },
+    /// Show description changes that apply would push to BigQuery
+    Plan {
+        /// Table specification (project.dataset.table); all tables if omitted
+        table: Option<String>,
+        /// Project ID to filter
+        #[arg(short, long, conflicts_with = "table")]
+        project: Option<String>,
+        /// Dataset ID to filter
+        #[arg(short, long, conflicts_with = "table")]
+        dataset: Option<String>,
+        /// Output format
+        #[arg(short, long, default_value = "table")]
+        output: String,
+    },
+    /// Push description changes to BigQuery via tables.patch
+    Apply {
+        /// Table specification (project.dataset.table); all tables if omitted
+        table: Option<String>,
+        /// Project ID to filter
+        #[arg(short, long, conflicts_with = "table")]
+        project: Option<String>,
+        /// Dataset ID to filter
+        #[arg(short, long, conflicts_with = "table")]
+        dataset: Option<String>,
+        /// Apply without asking for confirmation
+        #[arg(short, long)]
+        yes: bool,
+    },
+    /// Refresh local metadata from the BigQuery schema, keeping local descriptions
+    Pull {
+        /// Table specification (project.dataset.table); all tables if omitted
+        table: Option<String>,
+        /// Project ID to filter
+        #[arg(short, long, conflicts_with = "table")]
+        project: Option<String>,
+        /// Dataset ID to filter
+        #[arg(short, long, conflicts_with = "table")]
+        dataset: Option<String>,
+        /// Output format
+        #[arg(short, long, default_value = "table")]
+        output: String,
+    },
+    /// Report differences between local metadata and BigQuery (exits non-zero on drift)
+    Drift {
+        /// Table specification (project.dataset.table); all tables if omitted
+        table: Option<String>,
+        /// Project ID to filter
+        #[arg(short, long, conflicts_with = "table")]
+        project: Option<String>,
+        /// Dataset ID to filter
+        #[arg(short, long, conflicts_with = "table")]
+        dataset: Option<String>,
+        /// Directory of schema JSON dumps to compare against instead of the REST API
+        #[arg(long)]
+        schemas: Option<String>,
+        /// Output format
+        #[arg(short, long, default_value = "table")]
+        output: String,
+    },
+    /// Report description coverage of tables and columns
+    Coverage {
+        /// Project ID to filter
+        #[arg(short, long)]
+        project: Option<String>,
+        /// Dataset ID to filter
+        #[arg(short, long)]
+        dataset: Option<String>,
+        /// Fail if table or column coverage is below this percentage
+        #[arg(long)]
+        threshold: Option<f64>,
+        /// Output format
+        #[arg(short, long, default_value = "table")]
+        output: String,
+    },
+    /// Check metadata quality (exits non-zero on error-level findings)
+    Lint {
+        /// Table specification (project.dataset.table); all tables if omitted
+        table: Option<String>,
+        /// Project ID to filter
+        #[arg(short, long, conflicts_with = "table")]
+        project: Option<String>,
+        /// Dataset ID to filter
+        #[arg(short, long, conflicts_with = "table")]
+        dataset: Option<String>,
+        /// Output format (table, json, sarif)
+        #[arg(short, long, default_value = "table")]
+        output: String,
+    },
+    /// Generate documentation pages for the catalog
+    Docs {
+        /// Output directory
+        #[arg(long, default_value = "docs")]
+        out: String,
+        /// Project ID to filter
+        #[arg(short, long)]
+        project: Option<String>,
+        /// Dataset ID to filter
+        #[arg(short, long)]
+        dataset: Option<String>,
+        /// Output format (markdown, html)
+        #[arg(short = 'f', long, default_value = "markdown")]
+        format: String,
+    },
+    /// Serve the catalog over a local HTTP REST API
+    Serve {
+        /// Address to listen on
+        #[arg(long, default_value = "127.0.0.1:8080")]
+        addr: String,
+        /// Enable PUT/PATCH endpoints that update descriptions
+        #[arg(long)]
+        write: bool,
+    },
+    /// Run a Model Context Protocol server over stdin/stdout
+    Mcp,
+    /// Run a language server for editing metadata YAML files over stdin/stdout
+    Lsp,
+    /// Rebuild the search index
+    Reindex,
+    /// Browse and edit the catalog in an interactive terminal UI
+    Tui,
+    /// Show the change history of a table, or of the whole catalog (requires `init --git`)
+    History {
+        /// Table specification (project.dataset.table)
+        table: Option<String>,
+        /// Output format
+        #[arg(short, long, default_value = "table")]
+        output: String,
+    },
+    /// Show who last changed the table and column descriptions (requires `init --git`)
+    Blame {
+        /// Table specification (project.dataset.table)
+        table: String,
+        /// Output format
+        #[arg(short, long, default_value = "table")]
+        output: String,
+    },
+    /// Revert the most recent metadata changes
+    Undo {
+        /// Number of changes to revert
+        #[arg(long, default_value_t = 1)]
+        steps: usize,
+        /// Revert even if a table was changed again since
+        #[arg(long)]
+        force: bool,
+    },
+    /// Reapply changes reverted by `undo`
+    Redo {
+        /// Number of changes to reapply
+        #[arg(long, default_value_t = 1)]
+        steps: usize,
+        /// Reapply even if a table was changed again since
+        #[arg(long)]
+        force: bool,
+    },
+    /// Show the journal of metadata changes, newest first
+    Log {
+        /// Table specification (project.dataset.table)
+        table: Option<String>,
+        /// Maximum number of entries to show
+        #[arg(short = 'n', long)]
+        limit: Option<usize>,
+        /// Output format
+        #[arg(short, long, default_value = "table")]
+        output: String,
+    },
+    /// Rewrite table files and config.yaml stored in an older format version
+    Migrate {
+        /// Only list what would be rewritten
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Manage the storage backend
+    Storage {
+        #[command(subcommand)]
+        action: StorageAction,
     },
     /// Manage configuration
     Config {
@@ -139,6 +350,17 @@ pub enum ConfigAction {
     List,
 }
 
+#[derive(Subcommand)]
+pub enum StorageAction {
+    /// Copy every table from one backend to another (yaml or sqlite)
+    Copy {
+        /// Backend to copy from
+        from: String,
+        /// Backend to copy to
+        to: String,
+    },
+}
+
 pub fn parse_table_spec(table_spec: &str) -> Result<(String, String, String), String> {
     let parts: Vec<&str> = table_spec.split('.').collect();
     if parts.len() != 3 {
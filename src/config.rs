@@ -2,7 +2,9 @@ use anyhow::{Context, Result};
 use std::env;
 use std::path::PathBuf;
 
+use crate::migration;
 use crate::models::Config;
+use crate::storage::write_atomic;
 
 pub fn get_data_path() -> Result<PathBuf> {
     if let Ok(path) = env::var("BQ_META_PATH") {
@@ -24,6 +26,11 @@ pub fn get_data_dir() -> Result<PathBuf> {
     Ok(data_path.join("data"))
 }
 
+pub fn get_index_dir() -> Result<PathBuf> {
+    let data_path = get_data_path()?;
+    Ok(data_path.join("index"))
+}
+
 pub fn load_config() -> Result<Config> {
     let config_path = get_config_path()?;
     
@@ -34,7 +41,7 @@ pub fn load_config() -> Result<Config> {
     let content = std::fs::read_to_string(&config_path)
         .with_context(|| format!("Failed to read config file: {}", config_path.display()))?;
     
-    let config: Config = serde_yaml::from_str(&content)
+    let config = migration::parse_config(&content)
         .with_context(|| format!("Failed to parse config file: {}", config_path.display()))?;
     
     Ok(config)
@@ -49,11 +56,11 @@ pub fn save_config(config: &Config) -> Result<()> {
             .with_context(|| format!("Failed to create config directory: {}", parent.display()))?;
     }
 
-    let content = serde_yaml::to_string(config)
+    let content = migration::config_to_string(config)
         .context("Failed to serialize config")?;
     
-    std::fs::write(&config_path, content)
-        .with_context(|| format!("Failed to write config file: {}", config_path.display()))?;
+    write_atomic(&config_path, content.as_bytes())
+        .context("Failed to write config file")?;
     
     Ok(())
 }
@@ -0,0 +1,192 @@
+use anyhow::Result;
+use serde::Serialize;
+
+use crate::models::TableMetadata;
+use crate::storage::open_storage;
+
+/// Description counts for a table or a group of tables
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct Coverage {
+    pub tables: usize,
+    pub tables_documented: usize,
+    pub columns: usize,
+    pub columns_documented: usize,
+}
+
+impl Coverage {
+    /// Count descriptions of one table; nested fields are counted, removed columns are not
+    pub fn from_metadata(metadata: &TableMetadata) -> Self {
+        let columns: Vec<_> = metadata.flatten_columns().into_iter()
+            .filter(|(_, c)| !c.removed)
+            .collect();
+
+        Self {
+            tables: 1,
+            tables_documented: usize::from(has_text(&metadata.table.description)),
+            columns: columns.len(),
+            columns_documented: columns.iter().filter(|(_, c)| has_text(&c.description)).count(),
+        }
+    }
+
+    pub fn add(&mut self, other: &Coverage) {
+        self.tables += other.tables;
+        self.tables_documented += other.tables_documented;
+        self.columns += other.columns;
+        self.columns_documented += other.columns_documented;
+    }
+
+    pub fn table_percent(&self) -> f64 {
+        percent(self.tables_documented, self.tables)
+    }
+
+    pub fn column_percent(&self) -> f64 {
+        percent(self.columns_documented, self.columns)
+    }
+}
+
+fn has_text(description: &Option<String>) -> bool {
+    description.as_deref().is_some_and(|d| !d.trim().is_empty())
+}
+
+/// An empty set counts as fully covered
+fn percent(documented: usize, total: usize) -> f64 {
+    if total == 0 {
+        100.0
+    } else {
+        documented as f64 * 100.0 / total as f64
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TableCoverage {
+    pub table: String,
+    #[serde(flatten)]
+    pub coverage: Coverage,
+    pub table_percent: f64,
+    pub column_percent: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DatasetCoverage {
+    pub dataset: String,
+    #[serde(flatten)]
+    pub coverage: Coverage,
+    pub table_percent: f64,
+    pub column_percent: f64,
+    /// Named apart from the flattened `tables` count so the JSON keys don't collide
+    pub table_coverage: Vec<TableCoverage>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProjectCoverage {
+    pub project: String,
+    #[serde(flatten)]
+    pub coverage: Coverage,
+    pub table_percent: f64,
+    pub column_percent: f64,
+    pub datasets: Vec<DatasetCoverage>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CoverageReport {
+    #[serde(flatten)]
+    pub coverage: Coverage,
+    pub table_percent: f64,
+    pub column_percent: f64,
+    pub projects: Vec<ProjectCoverage>,
+}
+
+/// Compute description coverage for every table under the given filters
+pub fn compute_coverage(project_id: Option<&str>, dataset_id: Option<&str>) -> Result<CoverageReport> {
+    let mut projects: Vec<ProjectCoverage> = Vec::new();
+    let mut total = Coverage::default();
+
+    // Storage lists tables sorted, so tables of the same project and dataset are adjacent
+    let storage = open_storage()?;
+    for (project, dataset, table_name) in storage.list(project_id, dataset_id)? {
+        let metadata = storage.load(&project, &dataset, &table_name)?;
+        let coverage = Coverage::from_metadata(&metadata);
+        total.add(&coverage);
+
+        if projects.last().is_none_or(|p| p.project != project) {
+            projects.push(ProjectCoverage {
+                project: project.clone(),
+                coverage: Coverage::default(),
+                table_percent: 0.0,
+                column_percent: 0.0,
+                datasets: Vec::new(),
+            });
+        }
+        let project_coverage = projects.last_mut().unwrap();
+        project_coverage.coverage.add(&coverage);
+
+        if project_coverage.datasets.last().is_none_or(|d| d.dataset != dataset) {
+            project_coverage.datasets.push(DatasetCoverage {
+                dataset: dataset.clone(),
+                coverage: Coverage::default(),
+                table_percent: 0.0,
+                column_percent: 0.0,
+                table_coverage: Vec::new(),
+            });
+        }
+        let dataset_coverage = project_coverage.datasets.last_mut().unwrap();
+        dataset_coverage.coverage.add(&coverage);
+        dataset_coverage.table_coverage.push(TableCoverage {
+            table: table_name,
+            coverage,
+            table_percent: coverage.table_percent(),
+            column_percent: coverage.column_percent(),
+        });
+    }
+
+    for project in &mut projects {
+        project.table_percent = project.coverage.table_percent();
+        project.column_percent = project.coverage.column_percent();
+        for dataset in &mut project.datasets {
+            dataset.table_percent = dataset.coverage.table_percent();
+            dataset.column_percent = dataset.coverage.column_percent();
+        }
+    }
+
+    Ok(CoverageReport {
+        coverage: total,
+        table_percent: total.table_percent(),
+        column_percent: total.column_percent(),
+        projects,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_table_coverage() {
+        let metadata: TableMetadata = serde_yaml::from_str(r#"
+table: {name: t, project_id: p, dataset_id: d, description: "  "}
+columns:
+  - {name: id, type: STRING, description: Id}
+  - {name: gone, type: STRING, removed: true}
+  - name: rec
+    type: RECORD
+    description: Record
+    fields: [{name: x, type: STRING}, {name: y, type: STRING, description: Y}]
+"#).unwrap();
+
+        let coverage = Coverage::from_metadata(&metadata);
+        assert_eq!(coverage, Coverage { tables: 1, tables_documented: 0, columns: 4, columns_documented: 3 });
+        assert_eq!(coverage.table_percent(), 0.0);
+        assert_eq!(coverage.column_percent(), 75.0);
+        assert_eq!(Coverage::default().column_percent(), 100.0);
+
+        let dataset = DatasetCoverage {
+            dataset: "d".to_string(),
+            coverage,
+            table_percent: 0.0,
+            column_percent: 75.0,
+            table_coverage: Vec::new(),
+        };
+        let json = serde_json::to_string(&dataset).unwrap();
+        assert_eq!(json.matches("\"tables\"").count(), 1);
+    }
+}
@@ -0,0 +1,126 @@
+use crate::models::TableMetadata;
+
+/// Quote a string as a BigQuery double-quoted string literal
+pub fn quote_string(value: &str) -> String {
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+    for c in value.chars() {
+        match c {
+            '\\' => quoted.push_str("\\\\"),
+            '"' => quoted.push_str("\\\""),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Quote an identifier with backticks
+pub fn quote_identifier(value: &str) -> String {
+    format!("`{}`", value.replace('\\', "\\\\").replace('`', "\\`"))
+}
+
+/// Generate `ALTER ... SET OPTIONS` statements applying the table and column descriptions.
+///
+/// Only non-empty descriptions are emitted, and columns flagged as removed are skipped.
+/// `ALTER COLUMN` cannot address nested fields, so descriptions of RECORD children are
+/// listed as comments instead.
+pub fn generate_table_ddl(metadata: &TableMetadata) -> Vec<String> {
+    let table = &metadata.table;
+    let object = match table.table_type.as_deref() {
+        Some("VIEW") => "VIEW",
+        Some("MATERIALIZED_VIEW") => "MATERIALIZED VIEW",
+        _ => "TABLE",
+    };
+    let target = quote_identifier(&format!("{}.{}.{}", table.project_id, table.dataset_id, table.name));
+    let mut statements = Vec::new();
+
+    if let Some(ref desc) = table.description {
+        statements.push(format!(
+            "ALTER {} {} SET OPTIONS(description={});",
+            object, target, quote_string(desc)
+        ));
+    }
+
+    for (path, column) in metadata.flatten_columns() {
+        // Columns gone from BigQuery can't be altered
+        if column.removed {
+            continue;
+        }
+        let Some(ref desc) = column.description else { continue };
+
+        if path.contains('.') {
+            statements.push(format!(
+                "-- {}: nested field {} cannot be set with ALTER COLUMN",
+                target, path
+            ));
+        } else if object == "MATERIALIZED VIEW" {
+            statements.push(format!(
+                "-- {}: column {} of a materialized view cannot be set with ALTER COLUMN",
+                target, path
+            ));
+        } else {
+            statements.push(format!(
+                "ALTER {} {} ALTER COLUMN {} SET OPTIONS(description={});",
+                object, target, quote_identifier(&column.name), quote_string(desc)
+            ));
+        }
+    }
+
+    statements
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{ColumnInfo, TableInfo};
+
+    #[test]
+    fn test_quote_string() {
+        assert_eq!(quote_string("plain"), r#""plain""#);
+        assert_eq!(quote_string(r#"say "hi""#), r#""say \"hi\"""#);
+        assert_eq!(quote_string("a\\b\nc"), r#""a\\b\nc""#);
+        assert_eq!(quote_string("日本語"), "\"日本語\"");
+    }
+
+    #[test]
+    fn test_generate_table_ddl() {
+        let metadata = TableMetadata {
+            table: TableInfo {
+                name: "orders".to_string(),
+                project_id: "p".to_string(),
+                dataset_id: "d".to_string(),
+                description: Some("Customer's \"orders\"".to_string()),
+                ..Default::default()
+            },
+            columns: vec![
+                ColumnInfo {
+                    name: "order_id".to_string(),
+                    column_type: "STRING".to_string(),
+                    description: Some("Order id".to_string()),
+                    ..Default::default()
+                },
+                ColumnInfo {
+                    name: "note".to_string(),
+                    column_type: "STRING".to_string(),
+                    ..Default::default()
+                },
+                ColumnInfo {
+                    name: "legacy_id".to_string(),
+                    column_type: "STRING".to_string(),
+                    description: Some("Dropped in BigQuery".to_string()),
+                    removed: true,
+                    ..Default::default()
+                },
+            ],
+        };
+
+        assert_eq!(generate_table_ddl(&metadata), vec![
+            r#"ALTER TABLE `p.d.orders` SET OPTIONS(description="Customer's \"orders\"");"#,
+            r#"ALTER TABLE `p.d.orders` ALTER COLUMN `order_id` SET OPTIONS(description="Order id");"#,
+        ]);
+    }
+}
@@ -0,0 +1,196 @@
+use anyhow::{Context, Result};
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+
+use crate::models::{TableInfo, TableMetadata};
+use crate::storage::open_storage;
+
+/// Tables grouped by project and dataset, in sorted order
+pub type Catalog = BTreeMap<String, BTreeMap<String, Vec<TableMetadata>>>;
+
+/// Load every table under the given filters, grouped by project and dataset
+pub fn load_catalog(project_id: Option<&str>, dataset_id: Option<&str>) -> Result<Catalog> {
+    let mut catalog = Catalog::new();
+    let storage = open_storage()?;
+    for (project, dataset, table_name) in storage.list(project_id, dataset_id)? {
+        let metadata = storage.load(&project, &dataset, &table_name)?;
+        catalog.entry(project).or_default().entry(dataset).or_default().push(metadata);
+    }
+    Ok(catalog)
+}
+
+/// Escape text for use inside a Markdown table cell
+fn cell(text: &str) -> String {
+    text.trim()
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('|', "\\|")
+        .replace("\r\n", "<br>")
+        .replace('\n', "<br>")
+}
+
+/// First line of a description, for listings
+fn summary(description: &Option<String>) -> String {
+    description.as_deref()
+        .and_then(|d| d.lines().next())
+        .map(cell)
+        .unwrap_or_default()
+}
+
+pub fn render_index(catalog: &Catalog) -> String {
+    let mut out = String::from("# BigQuery Catalog\n\n## Table of Contents\n\n");
+    for (project, datasets) in catalog {
+        let _ = writeln!(out, "- [{}]({}/README.md)", project, project);
+        for (dataset, tables) in datasets {
+            let _ = writeln!(out, "  - [{}]({}/{}/README.md)", dataset, project, dataset);
+            for metadata in tables {
+                let _ = writeln!(out, "    - [{}]({}/{}/{}.md)", metadata.table.name, project, dataset, metadata.table.name);
+            }
+        }
+    }
+    out
+}
+
+pub fn render_project_page(project: &str, datasets: &BTreeMap<String, Vec<TableMetadata>>) -> String {
+    let mut out = format!("[Catalog](../README.md) / {}\n\n# {}\n\n", project, project);
+    out.push_str("| Dataset | Tables |\n|---|---|\n");
+    for (dataset, tables) in datasets {
+        let _ = writeln!(out, "| [{}]({}/README.md) | {} |", dataset, dataset, tables.len());
+    }
+    out
+}
+
+pub fn render_dataset_page(project: &str, dataset: &str, tables: &[TableMetadata]) -> String {
+    let mut out = format!(
+        "[Catalog](../../README.md) / [{}](../README.md) / {}\n\n# {}.{}\n\n",
+        project, dataset, project, dataset
+    );
+    out.push_str("| Table | Description |\n|---|---|\n");
+    for metadata in tables {
+        let _ = writeln!(out, "| [{}]({}.md) | {} |",
+                         metadata.table.name, metadata.table.name, summary(&metadata.table.description));
+    }
+    out
+}
+
+/// Table properties shown on a table page, as `(name, value)` pairs
+pub(crate) fn table_properties(table: &TableInfo) -> Vec<(&'static str, String)> {
+    let mut properties = Vec::new();
+    if let Some(ref table_type) = table.table_type {
+        properties.push(("Type", table_type.clone()));
+    }
+    if let Some(ref partitioning) = table.partitioning {
+        let field = partitioning.field.as_deref().unwrap_or("_PARTITIONTIME");
+        properties.push(("Partitioning", format!("{} ({})", partitioning.partition_type, field)));
+    }
+    if !table.clustering.is_empty() {
+        properties.push(("Clustering", table.clustering.join(", ")));
+    }
+    if !table.labels.is_empty() {
+        let labels: Vec<String> = table.labels.iter().map(|(k, v)| format!("{}={}", k, v)).collect();
+        properties.push(("Labels", labels.join(", ")));
+    }
+    properties
+}
+
+pub fn render_table_page(metadata: &TableMetadata) -> String {
+    let table = &metadata.table;
+    let mut out = format!(
+        "[Catalog](../../README.md) / [{}](../README.md) / [{}](README.md) / {}\n\n# {}.{}.{}\n\n",
+        table.project_id, table.dataset_id, table.name, table.project_id, table.dataset_id, table.name
+    );
+
+    if let Some(ref desc) = table.description {
+        let _ = writeln!(out, "{}\n", desc.trim());
+    }
+
+    let properties = table_properties(table);
+    if !properties.is_empty() {
+        out.push_str("| Property | Value |\n|---|---|\n");
+        for (name, value) in properties {
+            let _ = writeln!(out, "| {} | {} |", name, cell(&value));
+        }
+        out.push('\n');
+    }
+
+    out.push_str("## Columns\n\n");
+    if metadata.columns.is_empty() {
+        out.push_str("_No columns._\n");
+    } else {
+        out.push_str("| Name | Type | Mode | Description |\n|---|---|---|---|\n");
+        for (path, column) in metadata.flatten_columns() {
+            let removed = if column.removed { " _(removed)_" } else { "" };
+            let _ = writeln!(out, "| `{}`{} | {} | {} | {} |",
+                             path, removed, cell(&column.column_type),
+                             column.mode.as_deref().unwrap_or("NULLABLE"),
+                             column.description.as_deref().map(cell).unwrap_or_default());
+        }
+    }
+
+    if let Some(ref query) = table.view_query {
+        let _ = write!(out, "\n## View Query\n\n```sql\n{}\n```\n", query.trim());
+    }
+
+    out
+}
+
+/// Write a file, creating its parent directories
+pub(crate) fn write_file(path: &Path, content: &str) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create directory: {}", parent.display()))?;
+    }
+    fs::write(path, content)
+        .with_context(|| format!("Failed to write file: {}", path.display()))
+}
+
+/// Write the catalog as a tree of Markdown pages; returns the number of table pages written
+pub fn write_markdown_site(catalog: &Catalog, out_dir: &Path) -> Result<usize> {
+    let mut count = 0;
+    write_file(&out_dir.join("README.md"), &render_index(catalog))?;
+
+    for (project, datasets) in catalog {
+        let project_dir = out_dir.join(project);
+        write_file(&project_dir.join("README.md"), &render_project_page(project, datasets))?;
+
+        for (dataset, tables) in datasets {
+            let dataset_dir = project_dir.join(dataset);
+            write_file(&dataset_dir.join("README.md"), &render_dataset_page(project, dataset, tables))?;
+
+            for metadata in tables {
+                write_file(&dataset_dir.join(format!("{}.md", metadata.table.name)), &render_table_page(metadata))?;
+                count += 1;
+            }
+        }
+    }
+
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_table_page() {
+        let metadata: TableMetadata = serde_yaml::from_str(r#"
+table: {name: orders, project_id: p, dataset_id: d, description: "Orders | all"}
+columns:
+  - {name: id, type: STRING, mode: REQUIRED, description: "Order id\nunique"}
+  - name: items
+    type: RECORD
+    mode: REPEATED
+    fields: [{name: sku, type: STRING}]
+"#).unwrap();
+
+        let page = render_table_page(&metadata);
+        assert!(page.starts_with("[Catalog](../../README.md) / [p](../README.md) / [d](README.md) / orders\n"));
+        assert!(page.contains("| `id` | STRING | REQUIRED | Order id<br>unique |"));
+        assert!(page.contains("| `items.sku` | STRING | NULLABLE |  |"));
+
+        let dataset_page = render_dataset_page("p", "d", &[metadata]);
+        assert!(dataset_page.contains("| [orders](orders.md) | Orders \\| all |"));
+    }
+}
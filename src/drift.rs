@@ -0,0 +1,231 @@
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::path::Path;
+
+use crate::bigquery::{parse_schema, parse_table_resource};
+use crate::cli::parse_table_spec;
+use crate::models::{ColumnInfo, TableInfo, TableMetadata};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DriftKind {
+    /// Table exists in BigQuery but not in the local catalog
+    MissingTable,
+    /// Table exists in the local catalog but not in BigQuery
+    ExtraTable,
+    /// Column exists in BigQuery but not locally
+    AddedColumn,
+    /// Column exists locally but not in BigQuery
+    RemovedColumn,
+    TypeMismatch,
+    ModeMismatch,
+    DescriptionMismatch,
+}
+
+impl std::fmt::Display for DriftKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DriftKind::MissingTable => write!(f, "MISSING-TABLE"),
+            DriftKind::ExtraTable => write!(f, "EXTRA-TABLE"),
+            DriftKind::AddedColumn => write!(f, "ADDED-COL"),
+            DriftKind::RemovedColumn => write!(f, "REMOVED-COL"),
+            DriftKind::TypeMismatch => write!(f, "TYPE"),
+            DriftKind::ModeMismatch => write!(f, "MODE"),
+            DriftKind::DescriptionMismatch => write!(f, "DESC"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DriftItem {
+    pub table_path: String,
+    pub kind: DriftKind,
+    /// Dotted column path, `None` for table-level drift
+    pub column: Option<String>,
+    pub local: Option<String>,
+    pub remote: Option<String>,
+}
+
+/// Compare a local table with its BigQuery counterpart
+pub fn compare_tables(local: &TableMetadata, remote: &TableMetadata) -> Vec<DriftItem> {
+    let table_path = format!("{}.{}.{}", local.table.project_id, local.table.dataset_id, local.table.name);
+    let mut items = Vec::new();
+
+    if local.table.description != remote.table.description {
+        items.push(DriftItem {
+            table_path: table_path.clone(),
+            kind: DriftKind::DescriptionMismatch,
+            column: None,
+            local: local.table.description.clone(),
+            remote: remote.table.description.clone(),
+        });
+    }
+
+    compare_columns(&table_path, None, &local.columns, &remote.columns, &mut items);
+    items
+}
+
+fn compare_columns(table_path: &str, prefix: Option<&str>, local: &[ColumnInfo], remote: &[ColumnInfo], items: &mut Vec<DriftItem>) {
+    let path_of = |name: &str| match prefix {
+        Some(p) => format!("{}.{}", p, name),
+        None => name.to_string(),
+    };
+    let mut push = |kind, column: String, local: Option<String>, remote: Option<String>| {
+        items.push(DriftItem {
+            table_path: table_path.to_string(),
+            kind,
+            column: Some(column),
+            local,
+            remote,
+        });
+    };
+
+    // Columns already flagged as removed by `pull` are known to be gone, so they only
+    // count as drift if they reappear remotely
+    let local: Vec<&ColumnInfo> = local.iter().filter(|c| !c.removed).collect();
+
+    for column in &local {
+        if !remote.iter().any(|r| r.name == column.name) {
+            push(DriftKind::RemovedColumn, path_of(&column.name), Some(column.column_type.clone()), None);
+        }
+    }
+
+    let mut nested = Vec::new();
+    for remote_column in remote {
+        let path = path_of(&remote_column.name);
+        let Some(column) = local.iter().copied().find(|c| c.name == remote_column.name) else {
+            push(DriftKind::AddedColumn, path, None, Some(remote_column.column_type.clone()));
+            continue;
+        };
+
+        if column.column_type != remote_column.column_type {
+            push(DriftKind::TypeMismatch, path.clone(),
+                 Some(column.column_type.clone()), Some(remote_column.column_type.clone()));
+        }
+        // A missing mode means NULLABLE in BigQuery
+        let local_mode = column.mode.as_deref().unwrap_or("NULLABLE");
+        let remote_mode = remote_column.mode.as_deref().unwrap_or("NULLABLE");
+        if local_mode != remote_mode {
+            push(DriftKind::ModeMismatch, path.clone(),
+                 Some(local_mode.to_string()), Some(remote_mode.to_string()));
+        }
+        if column.description != remote_column.description {
+            push(DriftKind::DescriptionMismatch, path.clone(),
+                 column.description.clone(), remote_column.description.clone());
+        }
+        nested.push((path, column, remote_column));
+    }
+
+    for (path, column, remote_column) in nested {
+        compare_columns(table_path, Some(&path), &column.fields, &remote_column.fields, items);
+    }
+}
+
+/// Load remote tables from a directory of JSON dumps.
+///
+/// Each file is either a full table resource (`bq show --format=prettyjson`) or a bare
+/// schema (`bq show --schema`) named `project.dataset.table.json`.
+pub fn load_schema_dumps(dir: &Path) -> Result<Vec<TableMetadata>> {
+    let mut files: Vec<_> = std::fs::read_dir(dir)
+        .with_context(|| format!("Failed to read schema directory: {}", dir.display()))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|p| p.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    files.sort();
+
+    let mut tables = Vec::new();
+    for file in files {
+        let content = std::fs::read_to_string(&file)
+            .with_context(|| format!("Failed to read schema file: {}", file.display()))?;
+
+        let document: serde_json::Value = serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse schema file: {}", file.display()))?;
+        let is_resource = document.get("tableReference").is_some() || document.get("schema").is_some();
+
+        let metadata = if is_resource {
+            parse_table_resource(&content)
+                .with_context(|| format!("Failed to parse table resource: {}", file.display()))?
+        } else {
+            let stem = file.file_stem().and_then(|s| s.to_str()).unwrap_or_default();
+            let (project_id, dataset_id, name) = parse_table_spec(stem)
+                .map_err(|e| anyhow::anyhow!("{}: {}", file.display(), e))?;
+            TableMetadata {
+                table: TableInfo { name, project_id, dataset_id, ..Default::default() },
+                columns: parse_schema(&content)
+                    .with_context(|| format!("Failed to parse schema file: {}", file.display()))?,
+            }
+        };
+        tables.push(metadata);
+    }
+
+    Ok(tables)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compare_tables() {
+        let local: TableMetadata = serde_yaml::from_str(r#"
+table: {name: t, project_id: p, dataset_id: d, description: Same}
+columns:
+  - {name: id, type: INT64, description: Id}
+  - {name: local_only, type: STRING}
+  - {name: dropped, type: STRING, removed: true}
+  - {name: back, type: STRING, removed: true}
+  - name: rec
+    type: RECORD
+    fields: [{name: x, type: STRING, mode: NULLABLE}]
+"#).unwrap();
+        let remote: TableMetadata = serde_yaml::from_str(r#"
+table: {name: t, project_id: p, dataset_id: d, description: Same}
+columns:
+  - {name: id, type: STRING, mode: REQUIRED, description: Remote id}
+  - {name: back, type: STRING}
+  - name: rec
+    type: RECORD
+    fields: [{name: x, type: STRING}, {name: y, type: INT64}]
+"#).unwrap();
+
+        let items = compare_tables(&local, &remote);
+        let kinds: Vec<(DriftKind, Option<&str>)> = items.iter()
+            .map(|i| (i.kind, i.column.as_deref()))
+            .collect();
+        assert_eq!(kinds, vec![
+            (DriftKind::RemovedColumn, Some("local_only")),
+            (DriftKind::TypeMismatch, Some("id")),
+            (DriftKind::ModeMismatch, Some("id")),
+            (DriftKind::DescriptionMismatch, Some("id")),
+            (DriftKind::AddedColumn, Some("back")),
+            (DriftKind::AddedColumn, Some("rec.y")),
+        ]);
+        assert!(compare_tables(&remote, &remote).is_empty());
+    }
+
+    #[test]
+    fn test_load_schema_dumps() {
+        let dir = std::env::temp_dir().join(format!("bq-meta-drift-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+
+        std::fs::write(dir.join("p.d.bare.json"), r#"[{"name": "id", "type": "STRING"}]"#).unwrap();
+        std::fs::write(dir.join("resource.json"), r#"{
+            "tableReference": {"projectId": "p", "datasetId": "d", "tableId": "full"},
+            "schema": {"fields": [{"name": "ts", "type": "TIMESTAMP"}]}
+        }"#).unwrap();
+        let tables = load_schema_dumps(&dir).unwrap();
+        let names: Vec<&str> = tables.iter().map(|t| t.table.name.as_str()).collect();
+        assert_eq!(names, vec!["bare", "full"]);
+
+        // A broken table resource reports its own error instead of falling back to a bare schema
+        std::fs::write(dir.join("resource.json"), r#"{
+            "tableReference": {"projectId": "p", "datasetId": "d", "tableId": "full"},
+            "expirationTime": "soon"
+        }"#).unwrap();
+        let err = format!("{:#}", load_schema_dumps(&dir).unwrap_err());
+        assert!(err.contains("Invalid expirationTime"), "{}", err);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
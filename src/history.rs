@@ -0,0 +1,314 @@
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::OnceLock;
+
+use crate::config::{get_data_dir, load_config};
+use crate::lsp::{find_value, locate_column};
+use crate::migration;
+use crate::models::StorageBackend;
+
+/// A commit touching a table file
+#[derive(Debug, Clone, Serialize)]
+pub struct HistoryEntry {
+    pub commit: String,
+    pub author: String,
+    /// Commit time as `YYYY-MM-DD HH:MM` (UTC)
+    pub date: String,
+    pub message: String,
+}
+
+/// Last change to the table description or a column description
+#[derive(Debug, Clone, Serialize)]
+pub struct BlameEntry {
+    /// Dotted column path, or `None` for the table description
+    pub column: Option<String>,
+    pub description: Option<String>,
+    /// `None` when there is no description, or when its lines have uncommitted changes
+    pub change: Option<HistoryEntry>,
+}
+
+/// Commit message for a change to one table, or a count when several are touched
+pub fn change_message(action: &str, tables: &[(String, String, String)]) -> String {
+    match tables {
+        [(project, dataset, table)] => format!("{}: {}.{}.{}", action, project, dataset, table),
+        _ => format!("{}: {} tables", action, tables.len()),
+    }
+}
+
+/// The data directory when git history is enabled: the YAML backend is in use and
+/// `data/` is a git repository (see `bq-meta init --git`)
+fn repository() -> Result<Option<PathBuf>> {
+    if load_config()?.storage.backend != StorageBackend::Yaml {
+        return Ok(None);
+    }
+    let data_dir = get_data_dir()?;
+    Ok(data_dir.join(".git").exists().then_some(data_dir))
+}
+
+fn require_repository() -> Result<PathBuf> {
+    repository()?.ok_or_else(|| anyhow::anyhow!(
+        "Git history is not enabled (run `bq-meta init --git` with the yaml storage backend)"
+    ))
+}
+
+/// Run git in `repo`, returning stdout
+fn git(repo: &Path, args: &[&str]) -> Result<String> {
+    let mut command = Command::new("git");
+    command.arg("-C").arg(repo);
+    // Commits must not fail on machines without a configured identity
+    if !has_identity(repo) {
+        let user = std::env::var("USER").unwrap_or_else(|_| "bq-meta".to_string());
+        command.args(["-c", &format!("user.name={}", user), "-c", &format!("user.email={}@localhost", user)]);
+    }
+    let output = command.args(args).output().context("Failed to run git")?;
+    if !output.status.success() {
+        return Err(anyhow::anyhow!(
+            "git {} failed: {}",
+            args.first().unwrap_or(&""),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Whether git has a user identity configured, checked once per process since a single
+/// change runs git several times
+fn has_identity(repo: &Path) -> bool {
+    static HAS_IDENTITY: OnceLock<bool> = OnceLock::new();
+    *HAS_IDENTITY.get_or_init(|| {
+        ["user.name", "user.email"].iter().all(|key| {
+            Command::new("git").arg("-C").arg(repo).args(["config", key]).output()
+                .is_ok_and(|output| output.status.success())
+        })
+    })
+}
+
+fn relative_path(project_id: &str, dataset_id: &str, table_name: &str) -> String {
+    format!("{}/{}/{}.yaml", project_id, dataset_id, table_name)
+}
+
+/// Turn the data directory into a git repository and commit the existing metadata
+pub fn init_repository() -> Result<PathBuf> {
+    if load_config()?.storage.backend != StorageBackend::Yaml {
+        return Err(anyhow::anyhow!("Git history requires the yaml storage backend"));
+    }
+    let data_dir = get_data_dir()?;
+    if !data_dir.join(".git").exists() {
+        git(&data_dir, &["init", "--quiet"])?;
+    }
+    git(&data_dir, &["add", "--all"])?;
+    commit(&data_dir, "init: existing metadata", &[])?;
+    Ok(data_dir)
+}
+
+/// Commit the given tables' files if git history is enabled and they changed. Call it while
+/// holding the catalog lock, so concurrent changes don't contend for the git index.
+pub fn record(message: &str, tables: &[(String, String, String)]) -> Result<()> {
+    let Some(repo) = repository()? else {
+        return Ok(());
+    };
+    let paths: Vec<String> = tables.iter()
+        .map(|(project, dataset, table)| relative_path(project, dataset, table))
+        .collect();
+    record_in(&repo, message, &paths)
+        .with_context(|| format!("Failed to record history in {}", repo.display()))
+}
+
+fn record_in(repo: &Path, message: &str, paths: &[String]) -> Result<()> {
+    let mut args = vec!["add", "--all", "--"];
+    args.extend(paths.iter().map(String::as_str));
+    git(repo, &args)?;
+    commit(repo, message, paths)
+}
+
+/// Commit what is staged for `paths` (everything when empty), skipping empty commits
+fn commit(repo: &Path, message: &str, paths: &[String]) -> Result<()> {
+    let mut args = vec!["diff", "--cached", "--name-only", "--"];
+    args.extend(paths.iter().map(String::as_str));
+    if git(repo, &args)?.trim().is_empty() {
+        return Ok(());
+    }
+
+    let mut args = vec!["commit", "--quiet", "--no-verify", "-m", message, "--"];
+    args.extend(paths.iter().map(String::as_str));
+    git(repo, &args)?;
+    Ok(())
+}
+
+/// Commits touching one table, or the whole catalog, newest first
+pub fn history(table: Option<(&str, &str, &str)>) -> Result<Vec<HistoryEntry>> {
+    let repo = require_repository()?;
+    let mut args = vec!["log", "--format=%h%x1f%an%x1f%at%x1f%s"];
+    let path;
+    if let Some((project, dataset, table_name)) = table {
+        path = relative_path(project, dataset, table_name);
+        args.extend(["--follow", "--", &path]);
+    }
+    // A repository without commits has no history yet
+    let output = match git(&repo, &["rev-parse", "--verify", "--quiet", "HEAD"]) {
+        Ok(_) => git(&repo, &args)?,
+        Err(_) => String::new(),
+    };
+
+    Ok(output.lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.splitn(4, '\x1f').collect();
+            let [commit, author, time, message] = fields[..] else {
+                return None;
+            };
+            Some(HistoryEntry {
+                commit: commit.to_string(),
+                author: author.to_string(),
+                date: format_timestamp(time.parse().ok()?),
+                message: message.to_string(),
+            })
+        })
+        .collect())
+}
+
+/// Who last changed the table description and each column description
+pub fn blame(project_id: &str, dataset_id: &str, table_name: &str) -> Result<Vec<BlameEntry>> {
+    let repo = require_repository()?;
+    let path = relative_path(project_id, dataset_id, table_name);
+    let content = std::fs::read_to_string(repo.join(&path))
+        .with_context(|| format!("Table metadata not found: {}.{}.{}", project_id, dataset_id, table_name))?;
+    let metadata = migration::parse_table(&content)
+        .with_context(|| format!("Failed to parse {}", path))?;
+    let lines: Vec<&str> = content.lines().collect();
+
+    // Untracked files can't be blamed; every line is then uncommitted
+    let blamed = match git(&repo, &["blame", "--line-porcelain", "--", &path]) {
+        Ok(output) => parse_blame(&output),
+        Err(_) => Vec::new(),
+    };
+    let change_at = |line: Option<usize>| line.and_then(|line| latest_change(&lines, &blamed, line));
+
+    let columns_line = lines.iter().position(|l| l.starts_with("columns:")).unwrap_or(lines.len());
+    let table_line = find_value(&lines, 0, "description", None)
+        .map(|(line, _, _)| line)
+        .filter(|line| *line < columns_line && metadata.table.description.is_some());
+
+    let mut entries = vec![BlameEntry {
+        column: None,
+        description: metadata.table.description.clone(),
+        change: change_at(table_line),
+    }];
+    for (column_path, column) in metadata.flatten_columns() {
+        // An undocumented column has no change to report, even with a `description: null` line
+        let line = column.description.as_ref()
+            .and_then(|_| locate_column(&lines, &column_path, "description"))
+            .map(|range| range.start.line as usize);
+        entries.push(BlameEntry {
+            column: Some(column_path),
+            description: column.description.clone(),
+            change: change_at(line),
+        });
+    }
+    Ok(entries)
+}
+
+/// Commit of each line (by index) from `git blame --line-porcelain`; `None` if uncommitted
+fn parse_blame(output: &str) -> Vec<Option<(HistoryEntry, i64)>> {
+    let mut lines = Vec::new();
+    let mut commit = String::new();
+    let mut fields: HashMap<&str, &str> = HashMap::new();
+
+    for line in output.lines() {
+        if line.starts_with('\t') {
+            let time: i64 = fields.get("author-time").and_then(|t| t.parse().ok()).unwrap_or(0);
+            let uncommitted = commit.chars().all(|c| c == '0');
+            lines.push((!uncommitted).then(|| (HistoryEntry {
+                commit: commit.chars().take(7).collect(),
+                author: fields.get("author").unwrap_or(&"").to_string(),
+                date: format_timestamp(time),
+                message: fields.get("summary").unwrap_or(&"").to_string(),
+            }, time)));
+            fields.clear();
+            commit.clear();
+        } else if commit.is_empty() {
+            commit = line.split(' ').next().unwrap_or("").to_string();
+        } else if let Some((key, value)) = line.split_once(' ') {
+            fields.insert(key, value);
+        }
+    }
+    lines
+}
+
+/// Most recent commit among a description's lines: the key line plus any more indented
+/// continuation lines of a multi-line value
+fn latest_change(lines: &[&str], blamed: &[Option<(HistoryEntry, i64)>], line: usize) -> Option<HistoryEntry> {
+    let key_column = lines[line].find("description:").unwrap_or(0);
+    let end = (line + 1..lines.len())
+        .find(|&i| lines[i].len() - lines[i].trim_start().len() <= key_column)
+        .unwrap_or(lines.len());
+
+    let mut latest: Option<&(HistoryEntry, i64)> = None;
+    for entry in blamed.get(line..end.min(blamed.len()))? {
+        // Any uncommitted line makes the whole value uncommitted
+        let entry = entry.as_ref()?;
+        if latest.is_none_or(|(_, time)| entry.1 > *time) {
+            latest = Some(entry);
+        }
+    }
+    latest.map(|(entry, _)| entry.clone())
+}
+
+/// Format a Unix timestamp as `YYYY-MM-DD HH:MM` in UTC
+pub(crate) fn format_timestamp(secs: i64) -> String {
+    let days = secs.div_euclid(86_400);
+    let minutes = secs.rem_euclid(86_400) / 60;
+
+    // Civil date from days since 1970-01-01 (Howard Hinnant's algorithm)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!("{:04}-{:02}-{:02} {:02}:{:02}", year, month, day, minutes / 60, minutes % 60)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_messages_and_timestamps() {
+        let table = |name: &str| ("p".to_string(), "d".to_string(), name.to_string());
+        assert_eq!(change_message("import", &[table("t")]), "import: p.d.t");
+        assert_eq!(change_message("pull", &[table("a"), table("b")]), "pull: 2 tables");
+
+        assert_eq!(format_timestamp(0), "1970-01-01 00:00");
+        assert_eq!(format_timestamp(1_709_210_096), "2024-02-29 12:34");
+    }
+
+    #[test]
+    fn test_parse_blame() {
+        let output = "\
+abcdef1234567890 1 1 1
+author alice
+author-time 1709210096
+summary edit: p.d.t.id description
+\tname: id
+0000000000000000 2 2 1
+author Not Committed Yet
+author-time 1709210100
+summary Version of t.yaml from t.yaml
+\tdescription: draft
+";
+        let blamed = parse_blame(output);
+        assert_eq!(blamed.len(), 2);
+        let (entry, _) = blamed[0].as_ref().unwrap();
+        assert_eq!(entry.commit, "abcdef1");
+        assert_eq!(entry.author, "alice");
+        assert_eq!(entry.message, "edit: p.d.t.id description");
+        assert!(blamed[1].is_none());
+    }
+}
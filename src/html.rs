@@ -0,0 +1,319 @@
+use anyhow::Result;
+use std::fmt::Write as _;
+use std::path::Path;
+
+use crate::docs::{table_properties, write_file, Catalog};
+use crate::models::TableMetadata;
+
+const STYLE: &str = r#"
+body { font-family: -apple-system, "Segoe UI", Helvetica, Arial, sans-serif; margin: 0; display: flex; color: #24292f; }
+nav { width: 260px; min-height: 100vh; padding: 16px; background: #f6f8fa; border-right: 1px solid #d0d7de; box-sizing: border-box; }
+nav ul { list-style: none; padding-left: 12px; margin: 4px 0; }
+nav > ul { padding-left: 0; }
+main { flex: 1; padding: 16px 32px; max-width: 1100px; }
+a { color: #0969da; text-decoration: none; }
+a:hover { text-decoration: underline; }
+table { border-collapse: collapse; width: 100%; margin: 12px 0; }
+th, td { border: 1px solid #d0d7de; padding: 6px 10px; text-align: left; vertical-align: top; }
+th { background: #f6f8fa; }
+code, pre { font-family: SFMono-Regular, Consolas, monospace; }
+pre { background: #f6f8fa; padding: 12px; overflow-x: auto; }
+.breadcrumb { color: #57606a; margin-bottom: 8px; }
+.removed { color: #cf222e; font-size: 0.85em; }
+.tag { display: inline-block; min-width: 72px; font-size: 0.8em; font-weight: bold; }
+.result { margin: 10px 0; }
+.context { color: #57606a; }
+form.search input[type=text] { width: 100%; padding: 6px; box-sizing: border-box; }
+"#;
+
+const SEARCH_SCRIPT: &str = r#"
+(function () {
+  var index = JSON.parse(document.getElementById('search-index').textContent);
+  var form = document.getElementById('search-form');
+  var resultsEl = document.getElementById('results');
+  var params = new URLSearchParams(window.location.search);
+  ['q', 'scope'].forEach(function (key) { if (params.has(key)) form.elements[key].value = params.get(key); });
+  form.elements.regex.checked = params.get('regex') === 'on';
+  form.elements.case_sensitive.checked = params.get('case_sensitive') === 'on';
+
+  function escapeHtml(s) {
+    return s.replace(/[&<>"']/g, function (c) {
+      return { '&': '&amp;', '<': '&lt;', '>': '&gt;', '"': '&quot;', "'": '&#39;' }[c];
+    });
+  }
+
+  function matcher(pattern, regex, caseSensitive) {
+    if (regex) {
+      var re = new RegExp(pattern, caseSensitive ? '' : 'i');
+      return function (text) { return re.test(text); };
+    }
+    // NFKC folds full-width and half-width forms, as in search::matches_pattern
+    var norm = pattern.normalize('NFKC');
+    if (caseSensitive) return function (text) { return text.normalize('NFKC').indexOf(norm) !== -1; };
+    var lower = norm.toLowerCase();
+    return function (text) { return text.normalize('NFKC').toLowerCase().indexOf(lower) !== -1; };
+  }
+
+  // Same scopes and matching as the search command (the default scope matches table names
+  // only), but plain substring matching with no fuzzy fallback or scoring: results are
+  // listed by table path rather than ranked
+  function search(pattern, scope, regex, caseSensitive) {
+    var matches = matcher(pattern, regex, caseSensitive);
+    var all = scope === 'all';
+    var results = [];
+    index.forEach(function (t) {
+      if (scope === 'table') {
+        if (matches(t.table)) results.push([t, 'TABLE', t.table, null]);
+        return;
+      }
+      if (all || scope === 'desc') {
+        if (matches(t.table)) results.push([t, 'TABLE', t.table, null]);
+        if (t.description && matches(t.description)) results.push([t, 'DESC', t.description, null]);
+      }
+      t.columns.forEach(function (c) {
+        if ((all || scope === 'column') && matches(c.name)) results.push([t, 'COL', c.name, 'Column: ' + c.path]);
+        if ((all || scope === 'col-desc') && c.description && matches(c.description)) results.push([t, 'COL-DESC', c.description, 'Column: ' + c.path]);
+      });
+    });
+    var seen = {};
+    return results.filter(function (r) {
+      var key = [r[0].path, r[1], r[2], r[3] || ''].join(':');
+      if (seen[key]) return false;
+      seen[key] = true;
+      return true;
+    }).sort(function (a, b) { return a[0].path < b[0].path ? -1 : a[0].path > b[0].path ? 1 : 0; });
+  }
+
+  function render() {
+    var pattern = form.elements.q.value;
+    if (!pattern) { resultsEl.innerHTML = ''; return; }
+    var results;
+    try {
+      results = search(pattern, form.elements.scope.value, form.elements.regex.checked, form.elements.case_sensitive.checked);
+    } catch (e) {
+      resultsEl.textContent = 'Invalid pattern: ' + e.message;
+      return;
+    }
+    if (results.length === 0) { resultsEl.textContent = 'No matches found.'; return; }
+    resultsEl.innerHTML = results.map(function (r) {
+      return '<div class="result"><span class="tag">[' + r[1] + ']</span> <a href="' + r[0].url + '">' + escapeHtml(r[0].path) + '</a>' +
+        (r[3] ? ' <span class="context">(' + escapeHtml(r[3]) + ')</span>' : '') +
+        '<div><em>' + escapeHtml(r[2]) + '</em></div></div>';
+    }).join('');
+  }
+
+  form.addEventListener('input', render);
+  form.addEventListener('submit', function (e) { e.preventDefault(); render(); });
+  render();
+})();
+"#;
+
+/// Escape text for HTML element content and attribute values
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Navigation tree of projects and datasets; `root` is the relative path to the site root
+fn render_nav(catalog: &Catalog, root: &str) -> String {
+    let mut out = format!(
+        "<nav><form class=\"search\" action=\"{root}index.html\" method=\"get\"><input type=\"text\" name=\"q\" placeholder=\"Search tables\"></form>\n<ul>\n"
+    );
+    for (project, datasets) in catalog {
+        let _ = writeln!(out, "<li><a href=\"{root}{p}/index.html\">{}</a><ul>", escape(project), p = escape(project));
+        for dataset in datasets.keys() {
+            let _ = writeln!(out, "<li><a href=\"{root}{p}/{d}/index.html\">{}</a></li>",
+                             escape(dataset), p = escape(project), d = escape(dataset));
+        }
+        out.push_str("</ul></li>\n");
+    }
+    out.push_str("</ul></nav>\n");
+    out
+}
+
+fn render_page(title: &str, nav: &str, body: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>{}</style>\n</head>\n<body>\n{}<main>\n{}</main>\n</body>\n</html>\n",
+        escape(title), STYLE, nav, body
+    )
+}
+
+/// JSON search index with the fields `search::search_tables` matches on
+pub fn build_search_index(catalog: &Catalog) -> serde_json::Value {
+    let entries: Vec<serde_json::Value> = catalog.values()
+        .flat_map(|datasets| datasets.values())
+        .flatten()
+        .map(|metadata| {
+            let table = &metadata.table;
+            let columns: Vec<serde_json::Value> = metadata.flatten_columns().into_iter()
+                .map(|(path, column)| serde_json::json!({
+                    "path": path,
+                    "name": column.name,
+                    "description": column.description,
+                }))
+                .collect();
+            serde_json::json!({
+                "path": format!("{}.{}.{}", table.project_id, table.dataset_id, table.name),
+                "table": table.name,
+                "description": table.description,
+                "url": format!("{}/{}/{}.html", table.project_id, table.dataset_id, table.name),
+                "columns": columns,
+            })
+        })
+        .collect();
+    serde_json::Value::Array(entries)
+}
+
+pub fn render_index(catalog: &Catalog) -> String {
+    // `</` must not appear inside a script element
+    let index = build_search_index(catalog).to_string().replace("</", "<\\/");
+    let body = format!(
+        r#"<h1>BigQuery Catalog</h1>
+<form id="search-form">
+<p><input type="text" name="q" placeholder="Search pattern" autofocus style="width: 60%; padding: 6px;">
+<select name="scope">
+<option value="table">Table name</option>
+<option value="all">All fields</option>
+<option value="desc">Table description</option>
+<option value="column">Column name</option>
+<option value="col-desc">Column description</option>
+</select>
+<label><input type="checkbox" name="regex"> Regex</label>
+<label><input type="checkbox" name="case_sensitive"> Case sensitive</label></p>
+</form>
+<div id="results"></div>
+<script id="search-index" type="application/json">{}</script>
+<script>{}</script>
+"#,
+        index, SEARCH_SCRIPT
+    );
+    render_page("BigQuery Catalog", &render_nav(catalog, ""), &body)
+}
+
+pub fn render_project_page(catalog: &Catalog, project: &str) -> String {
+    let mut body = format!(
+        "<div class=\"breadcrumb\"><a href=\"../index.html\">Catalog</a> / {p}</div>\n<h1>{p}</h1>\n<table>\n<tr><th>Dataset</th><th>Tables</th></tr>\n",
+        p = escape(project)
+    );
+    for (dataset, tables) in &catalog[project] {
+        let _ = writeln!(body, "<tr><td><a href=\"{d}/index.html\">{d}</a></td><td>{}</td></tr>", tables.len(), d = escape(dataset));
+    }
+    body.push_str("</table>\n");
+    render_page(project, &render_nav(catalog, "../"), &body)
+}
+
+pub fn render_dataset_page(catalog: &Catalog, project: &str, dataset: &str) -> String {
+    let mut body = format!(
+        "<div class=\"breadcrumb\"><a href=\"../../index.html\">Catalog</a> / <a href=\"../index.html\">{p}</a> / {d}</div>\n<h1>{p}.{d}</h1>\n<table>\n<tr><th>Table</th><th>Description</th></tr>\n",
+        p = escape(project), d = escape(dataset)
+    );
+    for metadata in &catalog[project][dataset] {
+        let _ = writeln!(body, "<tr><td><a href=\"{t}.html\">{t}</a></td><td>{}</td></tr>",
+                         escape(metadata.table.description.as_deref().unwrap_or("")),
+                         t = escape(&metadata.table.name));
+    }
+    body.push_str("</table>\n");
+    render_page(&format!("{}.{}", project, dataset), &render_nav(catalog, "../../"), &body)
+}
+
+pub fn render_table_page(catalog: &Catalog, metadata: &TableMetadata) -> String {
+    let table = &metadata.table;
+    let full_name = format!("{}.{}.{}", table.project_id, table.dataset_id, table.name);
+    let mut body = format!(
+        "<div class=\"breadcrumb\"><a href=\"../../index.html\">Catalog</a> / <a href=\"../index.html\">{p}</a> / <a href=\"index.html\">{d}</a> / {t}</div>\n<h1>{}</h1>\n",
+        escape(&full_name), p = escape(&table.project_id), d = escape(&table.dataset_id), t = escape(&table.name)
+    );
+
+    if let Some(ref desc) = table.description {
+        let _ = writeln!(body, "<p>{}</p>", escape(desc.trim()).replace('\n', "<br>"));
+    }
+
+    let properties = table_properties(table);
+    if !properties.is_empty() {
+        body.push_str("<table>\n");
+        for (name, value) in properties {
+            let _ = writeln!(body, "<tr><th>{}</th><td>{}</td></tr>", name, escape(&value));
+        }
+        body.push_str("</table>\n");
+    }
+
+    body.push_str("<h2>Columns</h2>\n<table>\n<tr><th>Name</th><th>Type</th><th>Mode</th><th>Description</th></tr>\n");
+    for (path, column) in metadata.flatten_columns() {
+        let removed = if column.removed { " <span class=\"removed\">(removed)</span>" } else { "" };
+        let _ = writeln!(body, "<tr><td><code>{}</code>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                         escape(&path), removed, escape(&column.column_type),
+                         escape(column.mode.as_deref().unwrap_or("NULLABLE")),
+                         escape(column.description.as_deref().unwrap_or("")).replace('\n', "<br>"));
+    }
+    body.push_str("</table>\n");
+
+    if let Some(ref query) = table.view_query {
+        let _ = writeln!(body, "<h2>View Query</h2>\n<pre><code>{}</code></pre>", escape(query.trim()));
+    }
+
+    render_page(&full_name, &render_nav(catalog, "../../"), &body)
+}
+
+/// Write the catalog as a self-contained static HTML site; returns the number of table pages written
+pub fn write_html_site(catalog: &Catalog, out_dir: &Path) -> Result<usize> {
+    let mut count = 0;
+    write_file(&out_dir.join("index.html"), &render_index(catalog))?;
+
+    for (project, datasets) in catalog {
+        let project_dir = out_dir.join(project);
+        write_file(&project_dir.join("index.html"), &render_project_page(catalog, project))?;
+
+        for (dataset, tables) in datasets {
+            let dataset_dir = project_dir.join(dataset);
+            write_file(&dataset_dir.join("index.html"), &render_dataset_page(catalog, project, dataset))?;
+
+            for metadata in tables {
+                write_file(&dataset_dir.join(format!("{}.html", metadata.table.name)), &render_table_page(catalog, metadata))?;
+                count += 1;
+            }
+        }
+    }
+
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_search_index_and_escaping() {
+        let metadata: TableMetadata = serde_yaml::from_str(r#"
+table: {name: orders, project_id: p, dataset_id: d, description: "<script>alert(1)</script>"}
+columns:
+  - name: items
+    type: RECORD
+    fields: [{name: sku, type: STRING, description: Stock keeping unit}]
+"#).unwrap();
+        let mut catalog = Catalog::new();
+        catalog.entry("p".to_string()).or_default().entry("d".to_string()).or_default().push(metadata.clone());
+
+        let index = build_search_index(&catalog);
+        assert_eq!(index[0]["path"], "p.d.orders");
+        assert_eq!(index[0]["url"], "p/d/orders.html");
+        assert_eq!(index[0]["columns"][1]["path"], "items.sku");
+
+        let page = render_index(&catalog);
+        assert!(!page.contains("<script>alert(1)</script>"));
+        assert!(page.contains("<\\/script>"));
+
+        let table_page = render_table_page(&catalog, &metadata);
+        assert!(table_page.contains("&lt;script&gt;alert(1)&lt;/script&gt;"));
+        assert!(table_page.contains("<code>items.sku</code>"));
+    }
+}
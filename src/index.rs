@@ -0,0 +1,321 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::PathBuf;
+use unicode_normalization::UnicodeNormalization;
+
+use crate::config::get_index_dir;
+use crate::migration;
+use crate::models::TableMetadata;
+use crate::storage::{open_storage, write_atomic};
+
+/// Bumped whenever the on-disk layout or tokenization changes; older indexes are rebuilt
+const INDEX_VERSION: u32 = 2;
+
+/// NFKC-normalize text, folding full-width ASCII (`ＩＤ`) and half-width katakana (`ｶﾅ`)
+/// into their standard forms
+pub fn normalize(text: &str) -> String {
+    text.nfkc().collect()
+}
+
+/// Han, kana and Hangul, which are written without spaces between words
+fn is_cjk(c: char) -> bool {
+    matches!(c as u32,
+        0x3040..=0x30FF     // Hiragana, Katakana
+        | 0x3400..=0x4DBF   // CJK Extension A
+        | 0x4E00..=0x9FFF   // CJK Unified Ideographs
+        | 0xAC00..=0xD7AF   // Hangul syllables
+        | 0xF900..=0xFAFF   // CJK Compatibility Ideographs
+        | 0x20000..=0x2FA1F // CJK Extensions B-F and supplement
+    )
+}
+
+/// Split normalized text into lowercase tokens: alphanumeric words, and overlapping
+/// bigrams for runs of CJK characters (a lone CJK character is its own token)
+pub fn tokenize(text: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut word = String::new();
+    let mut cjk: Vec<char> = Vec::new();
+
+    let flush_cjk = |cjk: &mut Vec<char>, tokens: &mut Vec<String>| {
+        if cjk.len() == 1 {
+            tokens.push(cjk[0].to_string());
+        }
+        tokens.extend(cjk.windows(2).map(|pair| pair.iter().collect::<String>()));
+        cjk.clear();
+    };
+
+    for c in normalize(text).to_lowercase().chars() {
+        if is_cjk(c) {
+            if !word.is_empty() {
+                tokens.push(std::mem::take(&mut word));
+            }
+            cjk.push(c);
+        } else {
+            flush_cjk(&mut cjk, &mut tokens);
+            if c.is_alphanumeric() {
+                word.push(c);
+            } else if !word.is_empty() {
+                tokens.push(std::mem::take(&mut word));
+            }
+        }
+    }
+    flush_cjk(&mut cjk, &mut tokens);
+    if !word.is_empty() {
+        tokens.push(word);
+    }
+    tokens
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndexedTable {
+    pub project_id: String,
+    pub dataset_id: String,
+    /// Table name as listed by storage (the file name for YAML)
+    pub table_name: String,
+    /// Modification time in nanoseconds since the epoch, as reported by storage
+    pub modified: u64,
+    pub size: u64,
+    pub metadata: TableMetadata,
+}
+
+/// Persistent inverted index over table names, descriptions, column names and column descriptions
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct SearchIndex {
+    version: u32,
+    /// Indexed tables keyed by `project.dataset.table`
+    tables: BTreeMap<String, IndexedTable>,
+    /// Token to the keys of the tables containing it
+    postings: BTreeMap<String, BTreeSet<String>>,
+    #[serde(skip)]
+    dirty: bool,
+    /// Tables the last `update` skipped because a newer bq-meta wrote them
+    #[serde(skip)]
+    newer_format: Vec<(String, String, String)>,
+}
+
+fn index_path() -> Result<PathBuf> {
+    Ok(get_index_dir()?.join("search.json"))
+}
+
+fn table_tokens(metadata: &TableMetadata, table_name: &str) -> BTreeSet<String> {
+    let mut tokens: BTreeSet<String> = tokenize(table_name).into_iter().collect();
+    if let Some(ref desc) = metadata.table.description {
+        tokens.extend(tokenize(desc));
+    }
+    for (_, column) in metadata.flatten_columns() {
+        tokens.extend(tokenize(&column.name));
+        if let Some(ref desc) = column.description {
+            tokens.extend(tokenize(desc));
+        }
+    }
+    tokens
+}
+
+impl SearchIndex {
+    /// Load the index from disk, or `None` if it has not been built (or is from another version)
+    pub fn load() -> Result<Option<Self>> {
+        let path = index_path()?;
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read search index: {}", path.display()))?;
+        let index: SearchIndex = match serde_json::from_str(&content) {
+            Ok(index) => index,
+            Err(_) => return Ok(None),
+        };
+        Ok((index.version == INDEX_VERSION).then_some(index))
+    }
+
+    /// Build a fresh index of every table
+    pub fn build() -> Result<Self> {
+        let mut index = SearchIndex { version: INDEX_VERSION, dirty: true, ..Default::default() };
+        index.update()?;
+        Ok(index)
+    }
+
+    /// Write the index to disk if it changed since it was loaded
+    pub fn save(&mut self) -> Result<()> {
+        if !self.dirty {
+            return Ok(());
+        }
+        let path = index_path()?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create directory: {}", parent.display()))?;
+        }
+        let content = serde_json::to_string(self).context("Failed to serialize search index")?;
+        write_atomic(&path, content.as_bytes()).context("Failed to write search index")?;
+        self.dirty = false;
+        Ok(())
+    }
+
+    /// Tables the last `update` skipped because a newer bq-meta wrote them, as
+    /// `(project, dataset, table)`
+    pub fn newer_format(&self) -> &[(String, String, String)] {
+        &self.newer_format
+    }
+
+    pub fn len(&self) -> usize {
+        self.tables.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tables.is_empty()
+    }
+
+    fn remove(&mut self, key: &str) {
+        if let Some(entry) = self.tables.remove(key) {
+            for token in table_tokens(&entry.metadata, &entry.table_name) {
+                if let Some(keys) = self.postings.get_mut(&token) {
+                    keys.remove(key);
+                    if keys.is_empty() {
+                        self.postings.remove(&token);
+                    }
+                }
+            }
+            self.dirty = true;
+        }
+    }
+
+    fn insert(&mut self, key: String, entry: IndexedTable) {
+        for token in table_tokens(&entry.metadata, &entry.table_name) {
+            self.postings.entry(token).or_default().insert(key.clone());
+        }
+        self.tables.insert(key, entry);
+        self.dirty = true;
+    }
+
+    /// Re-index tables that changed since the last update and drop deleted ones.
+    /// Returns the number of tables added, updated or removed.
+    pub fn update(&mut self) -> Result<usize> {
+        let mut changed = 0;
+        let mut seen = BTreeSet::new();
+        self.newer_format.clear();
+
+        let storage = open_storage()?;
+        for (project_id, dataset_id, table_name) in storage.list(None, None)? {
+            let key = format!("{}.{}.{}", project_id, dataset_id, table_name);
+            let (modified, size) = storage.stamp(&project_id, &dataset_id, &table_name)?;
+            seen.insert(key.clone());
+
+            if let Some(entry) = self.tables.get(&key) {
+                if entry.modified == modified && entry.size == size {
+                    continue;
+                }
+            }
+
+            self.remove(&key);
+            // Unparseable files are skipped, as in the scanning search; they aren't indexed, so
+            // they are retried on every update
+            match storage.load(&project_id, &dataset_id, &table_name) {
+                Ok(metadata) => self.insert(key, IndexedTable { project_id, dataset_id, table_name, modified, size, metadata }),
+                Err(err) if migration::is_newer_format(&err) => self.newer_format.push((project_id, dataset_id, table_name)),
+                Err(_) => {}
+            }
+            changed += 1;
+        }
+
+        let deleted: Vec<String> = self.tables.keys().filter(|k| !seen.contains(*k)).cloned().collect();
+        for key in deleted {
+            self.remove(&key);
+            changed += 1;
+        }
+
+        Ok(changed)
+    }
+
+    /// Tables that may contain `pattern` as a substring, in key order.
+    ///
+    /// Every alphanumeric run of the pattern must be a substring of some token of a matching
+    /// table, so the token dictionary narrows the candidates; callers still verify matches.
+    pub fn candidates(&self, pattern: Option<&str>, project_id: Option<&str>, dataset_id: Option<&str>) -> Vec<&IndexedTable> {
+        let in_scope = |entry: &&IndexedTable| {
+            project_id.is_none_or(|p| p == entry.project_id) && dataset_id.is_none_or(|d| d == entry.dataset_id)
+        };
+
+        let query_tokens = pattern.map(tokenize).unwrap_or_default();
+        if query_tokens.is_empty() {
+            return self.tables.values().filter(in_scope).collect();
+        }
+
+        let mut keys: Option<BTreeSet<&String>> = None;
+        for query_token in &query_tokens {
+            let matching: BTreeSet<&String> = self.postings.iter()
+                .filter(|(token, _)| token.contains(query_token.as_str()))
+                .flat_map(|(_, keys)| keys)
+                .collect();
+            keys = Some(match keys {
+                Some(previous) => previous.intersection(&matching).copied().collect(),
+                None => matching,
+            });
+        }
+
+        keys.unwrap_or_default().into_iter()
+            .filter_map(|key| self.tables.get(key))
+            .filter(in_scope)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(yaml: &str) -> IndexedTable {
+        let metadata: TableMetadata = serde_yaml::from_str(yaml).unwrap();
+        IndexedTable {
+            project_id: metadata.table.project_id.clone(),
+            dataset_id: metadata.table.dataset_id.clone(),
+            table_name: metadata.table.name.clone(),
+            modified: 0,
+            size: 0,
+            metadata,
+        }
+    }
+
+    #[test]
+    fn test_tokenize() {
+        assert_eq!(tokenize("user_events: Daily-Summary"), vec!["user", "events", "daily", "summary"]);
+        assert_eq!(tokenize("ユーザーＩＤ（購入）"), vec!["ユー", "ーザ", "ザー", "id", "購入"]);
+        assert_eq!(tokenize("ｶﾅ 表"), vec!["カナ", "表"]);
+    }
+
+    #[test]
+    fn test_candidates() {
+        let mut index = SearchIndex::default();
+        index.insert("p.d.user_events".to_string(), entry(r#"
+table: {name: user_events, project_id: p, dataset_id: d, description: Tracking events}
+columns: [{name: customer_id, type: STRING}]
+"#));
+        index.insert("p.x.orders".to_string(), entry(r#"
+table: {name: orders, project_id: p, dataset_id: x}
+columns: [{name: customer_id, type: STRING, description: Buyer}]
+"#));
+
+        fn names(index: &SearchIndex, pattern: Option<&str>, dataset: Option<&str>) -> Vec<String> {
+            index.candidates(pattern, Some("p"), dataset).iter().map(|e| e.table_name.clone()).collect()
+        }
+        assert_eq!(names(&index, Some("r_ev"), None), vec!["user_events"]);
+        assert_eq!(names(&index, Some("CUSTOMER"), None), vec!["user_events", "orders"]);
+        assert_eq!(names(&index, Some("customer"), Some("x")), vec!["orders"]);
+        assert!(names(&index, Some("missing"), None).is_empty());
+        assert_eq!(names(&index, Some("^.*$"), None).len(), 2);
+        assert_eq!(names(&index, None, None).len(), 2);
+
+        index.insert("p.x.purchases".to_string(), entry(r#"
+table: {name: purchases, project_id: p, dataset_id: x, description: ユーザーの購入履歴}
+columns: []
+"#));
+        assert_eq!(names(&index, Some("購入履歴"), None), vec!["purchases"]);
+        assert_eq!(names(&index, Some("ﾕｰｻﾞｰ"), None), vec!["purchases"]);
+        assert!(names(&index, Some("履歴書"), None).is_empty());
+        index.remove("p.x.purchases");
+
+        index.remove("p.x.orders");
+        assert!(!index.postings.contains_key("buyer"));
+        assert_eq!(names(&index, Some("customer"), None), vec!["user_events"]);
+    }
+}
@@ -0,0 +1,497 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::{self, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::config::get_data_path;
+use crate::history::{self, format_timestamp};
+use crate::migration::{self, NewerFormatError, FORMAT_VERSION};
+use crate::models::TableMetadata;
+use crate::storage::{open_storage, transaction, CatalogLock, Storage};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JournalAction {
+    Save,
+    Undo,
+    Redo,
+}
+
+impl std::fmt::Display for JournalAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            JournalAction::Save => "save",
+            JournalAction::Undo => "undo",
+            JournalAction::Redo => "redo",
+        })
+    }
+}
+
+/// One line of `journal.jsonl`: a table's state before and after a write (`None` when the
+/// table didn't exist). Entries are numbered by their line, starting at 1.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JournalEntry {
+    /// Format of the `before`/`after` snapshots; entries without it are version 0
+    #[serde(default)]
+    pub format_version: u32,
+    /// Unix timestamp in seconds
+    pub time: i64,
+    pub action: JournalAction,
+    /// Entry reverted by an undo or reapplied by a redo
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target: Option<usize>,
+    pub project_id: String,
+    pub dataset_id: String,
+    pub table_name: String,
+    pub before: Option<TableMetadata>,
+    /// The table existed but couldn't be read, so `before` is unknown and can't be restored
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub before_unreadable: bool,
+    pub after: Option<TableMetadata>,
+}
+
+impl JournalEntry {
+    pub fn table_path(&self) -> String {
+        format!("{}.{}.{}", self.project_id, self.dataset_id, self.table_name)
+    }
+}
+
+/// A journal entry as shown by `bq-meta log`
+#[derive(Debug, Clone, Serialize)]
+pub struct LogEntry {
+    pub id: usize,
+    pub date: String,
+    pub action: JournalAction,
+    pub table_path: String,
+    pub summary: String,
+    /// Reverted by `undo` and not redone
+    pub undone: bool,
+}
+
+fn journal_path() -> Result<PathBuf> {
+    Ok(get_data_path()?.join("journal.jsonl"))
+}
+
+fn now() -> i64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs() as i64)
+}
+
+/// Append an entry; callers hold the catalog lock, so entries are never interleaved
+fn append(entry: &JournalEntry) -> Result<()> {
+    let path = journal_path()?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create directory: {}", dir.display()))?;
+    }
+    let mut line = serde_json::to_string(entry)?;
+    line.push('\n');
+    append_line(&path, &line).with_context(|| format!("Failed to append to {}", path.display()))
+}
+
+/// Append `line` with a single write, first dropping an incomplete last line left by an
+/// interrupted append so the new entry starts on a line of its own
+fn append_line(path: &Path, line: &str) -> std::io::Result<()> {
+    let mut file = OpenOptions::new().create(true).read(true).append(true).open(path)?;
+    let len = file.metadata()?.len();
+    if len > 0 {
+        let mut last = [0];
+        file.seek(SeekFrom::Start(len - 1))?;
+        file.read_exact(&mut last)?;
+        if last[0] != b'\n' {
+            let content = fs::read(path)?;
+            let complete = content.iter().rposition(|&b| b == b'\n').map_or(0, |i| i + 1);
+            file.set_len(complete as u64)?;
+        }
+    }
+    file.write_all(line.as_bytes())
+}
+
+/// Journal a write of a table; writes that change nothing are skipped. `before_unreadable`
+/// marks a table that existed but couldn't be read, which then can't be undone.
+pub(crate) fn record(
+    project_id: &str,
+    dataset_id: &str,
+    table_name: &str,
+    before: Option<TableMetadata>,
+    before_unreadable: bool,
+    after: Option<&TableMetadata>,
+) -> Result<()> {
+    if !before_unreadable && before.as_ref() == after {
+        return Ok(());
+    }
+    append(&JournalEntry {
+        format_version: FORMAT_VERSION,
+        time: now(),
+        action: JournalAction::Save,
+        target: None,
+        project_id: project_id.to_string(),
+        dataset_id: dataset_id.to_string(),
+        table_name: table_name.to_string(),
+        before,
+        before_unreadable,
+        after: after.cloned(),
+    })
+    .with_context(|| format!("Saved {}.{}.{}, but failed to update the undo journal", project_id, dataset_id, table_name))
+}
+
+pub fn read_journal() -> Result<Vec<JournalEntry>> {
+    let path = journal_path()?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    parse_journal(&content, &path)
+}
+
+fn parse_journal(content: &str, path: &Path) -> Result<Vec<JournalEntry>> {
+    let lines: Vec<&str> = content.lines().collect();
+    let torn = !content.is_empty() && !content.ends_with('\n');
+    lines.iter()
+        .enumerate()
+        .filter_map(|(index, line)| match parse_entry(line) {
+            // An interrupted append; the next append truncates it
+            Err(_) if torn && index + 1 == lines.len() => None,
+            result => Some(result
+                .with_context(|| format!("Invalid journal entry at {}:{}", path.display(), index + 1))),
+        })
+        .collect()
+}
+
+/// Parse a journal line, upgrading snapshots recorded in an older format
+fn parse_entry(line: &str) -> Result<JournalEntry> {
+    let mut value: serde_json::Value = serde_json::from_str(line)?;
+    let version = value.get("format_version").and_then(|v| v.as_u64()).unwrap_or(0) as u32;
+    if version > FORMAT_VERSION {
+        return Err(NewerFormatError { found: version }.into());
+    }
+    if version < FORMAT_VERSION {
+        for key in ["before", "after"] {
+            if let Some(snapshot) = value.get_mut(key).filter(|s| !s.is_null()) {
+                let metadata = migration::upgrade_table(serde_yaml::to_value(&*snapshot)?, version)?;
+                *snapshot = serde_json::to_value(metadata)?;
+            }
+        }
+    }
+    Ok(serde_json::from_value(value)?)
+}
+
+/// Replay the journal into the ids of changes that can be undone and those that can be
+/// redone, most recent last. A new save discards the redo stack, as in an editor.
+fn replay(entries: &[JournalEntry]) -> (Vec<usize>, Vec<usize>) {
+    let (mut done, mut undone) = (Vec::new(), Vec::new());
+    for (index, entry) in entries.iter().enumerate() {
+        match (entry.action, entry.target) {
+            (JournalAction::Save, _) => {
+                done.push(index + 1);
+                undone.clear();
+            }
+            (JournalAction::Undo, Some(target)) => {
+                done.retain(|&id| id != target);
+                undone.push(target);
+            }
+            (JournalAction::Redo, Some(target)) => {
+                undone.retain(|&id| id != target);
+                done.push(target);
+            }
+            _ => {}
+        }
+    }
+    (done, undone)
+}
+
+/// Put a table into `state` (deleting it for `None`), returning what was there before and
+/// whether it was unreadable. Unless `force` is set, the table must still be in `expected`,
+/// the state the entry left it in, so changes made since aren't silently discarded.
+fn restore(
+    storage: &dyn Storage,
+    entry: &JournalEntry,
+    expected: Option<&TableMetadata>,
+    state: Option<&TableMetadata>,
+    force: bool,
+) -> Result<(Option<TableMetadata>, bool)> {
+    let (project_id, dataset_id, table_name) = (&entry.project_id, &entry.dataset_id, &entry.table_name);
+    let exists = storage.exists(project_id, dataset_id, table_name)?;
+    let current = if !exists {
+        None
+    } else if force {
+        // A table from a newer bq-meta is never overwritten, not even with --force
+        match storage.load(project_id, dataset_id, table_name) {
+            Ok(metadata) => Some(metadata),
+            Err(err) if migration::is_newer_format(&err) => return Err(err),
+            Err(_) => None,
+        }
+    } else {
+        Some(storage.load(project_id, dataset_id, table_name)?)
+    };
+    if !force && current.as_ref() != expected {
+        return Err(anyhow::anyhow!(
+            "{} was changed after this change was recorded (use --force to overwrite those changes)",
+            entry.table_path()
+        ));
+    }
+    match state {
+        Some(metadata) => storage.save(metadata)?,
+        None if exists => storage.delete(project_id, dataset_id, table_name)?,
+        None => {}
+    }
+    let unreadable = exists && current.is_none();
+    Ok((current, unreadable))
+}
+
+/// Revert (`Undo`) or reapply (`Redo`) the last `steps` changes and commit them to git history
+/// when enabled, returning the entries applied
+fn step(action: JournalAction, steps: usize, force: bool) -> Result<Vec<JournalEntry>> {
+    let _lock = CatalogLock::acquire()?;
+    let entries = read_journal()?;
+    let (done, undone) = replay(&entries);
+    let available = if action == JournalAction::Undo { done } else { undone };
+    if available.is_empty() {
+        return Err(anyhow::anyhow!("Nothing to {}", action));
+    }
+    if steps > available.len() {
+        let past = if action == JournalAction::Undo { "undone" } else { "redone" };
+        return Err(anyhow::anyhow!("Only {} change(s) can be {}", available.len(), past));
+    }
+
+    let storage = open_storage()?;
+    let mut applied = Vec::new();
+    for &id in available.iter().rev().take(steps) {
+        let entry = &entries[id - 1];
+        if action == JournalAction::Undo && entry.before_unreadable {
+            return Err(anyhow::anyhow!(
+                "Cannot undo change #{}: {} couldn't be read before it, so there is nothing to restore",
+                id, entry.table_path()
+            ));
+        }
+        let (expected, state) = if action == JournalAction::Undo {
+            (&entry.after, &entry.before)
+        } else {
+            (&entry.before, &entry.after)
+        };
+        transaction(&*storage, || {
+            let (current, unreadable) = restore(&*storage, entry, expected.as_ref(), state.as_ref(), force)
+                .with_context(|| format!("Failed to {} change #{} to {}", action, id, entry.table_path()))?;
+            append(&JournalEntry {
+                format_version: FORMAT_VERSION,
+                time: now(),
+                action,
+                target: Some(id),
+                before: current,
+                before_unreadable: unreadable,
+                after: state.clone(),
+                ..entry.clone()
+            })
+        })?;
+        applied.push(entry.clone());
+    }
+
+    let mut tables: Vec<(String, String, String)> = Vec::new();
+    for entry in &applied {
+        let table = (entry.project_id.clone(), entry.dataset_id.clone(), entry.table_name.clone());
+        if !tables.contains(&table) {
+            tables.push(table);
+        }
+    }
+    history::record(&history::change_message(&action.to_string(), &tables), &tables)?;
+    Ok(applied)
+}
+
+/// Revert the last `steps` changes, newest first. Fails if a table was changed since,
+/// unless `force` is set.
+pub fn undo(steps: usize, force: bool) -> Result<Vec<JournalEntry>> {
+    step(JournalAction::Undo, steps, force)
+}
+
+/// Reapply the last `steps` undone changes. Fails if a table was changed since, unless
+/// `force` is set.
+pub fn redo(steps: usize, force: bool) -> Result<Vec<JournalEntry>> {
+    step(JournalAction::Redo, steps, force)
+}
+
+/// Journal entries, newest first, optionally for one table
+pub fn log(table: Option<(&str, &str, &str)>, limit: Option<usize>) -> Result<Vec<LogEntry>> {
+    let entries = read_journal()?;
+    let (_, undone) = replay(&entries);
+    Ok(entries.iter()
+        .enumerate()
+        .rev()
+        .filter(|(_, entry)| table.is_none_or(|(project, dataset, name)| {
+            entry.project_id == project && entry.dataset_id == dataset && entry.table_name == name
+        }))
+        .take(limit.unwrap_or(usize::MAX))
+        .map(|(index, entry)| LogEntry {
+            id: index + 1,
+            date: format_timestamp(entry.time),
+            action: entry.action,
+            table_path: entry.table_path(),
+            summary: summarize(entry),
+            undone: undone.contains(&(index + 1)),
+        })
+        .collect())
+}
+
+/// Short description of what an entry changed
+pub fn summarize(entry: &JournalEntry) -> String {
+    match (entry.action, entry.target) {
+        (JournalAction::Undo, Some(target)) => return format!("reverted #{}", target),
+        (JournalAction::Redo, Some(target)) => return format!("reapplied #{}", target),
+        _ => {}
+    }
+
+    if entry.before_unreadable {
+        return "replaced an unreadable table".to_string();
+    }
+    let (before, after) = match (&entry.before, &entry.after) {
+        (None, _) => return "created".to_string(),
+        (_, None) => return "deleted".to_string(),
+        (Some(before), Some(after)) => (before, after),
+    };
+
+    let before_columns: BTreeMap<_, _> = before.flatten_columns().into_iter().collect();
+    let after_columns: BTreeMap<_, _> = after.flatten_columns().into_iter().collect();
+    let added = after_columns.keys().filter(|path| !before_columns.contains_key(*path)).count();
+    let removed = before_columns.keys().filter(|path| !after_columns.contains_key(*path)).count();
+    let (mut descriptions, mut modified) = (0, 0);
+    for (path, old) in &before_columns {
+        let Some(new) = after_columns.get(path) else { continue };
+        if old.description != new.description {
+            descriptions += 1;
+        }
+        if (&old.column_type, &old.mode, &old.policy_tags, old.removed)
+            != (&new.column_type, &new.mode, &new.policy_tags, new.removed) {
+            modified += 1;
+        }
+    }
+
+    let mut parts = Vec::new();
+    if before.table.description != after.table.description {
+        parts.push("table description changed".to_string());
+    }
+    for (count, what) in [
+        (descriptions, "column description(s) changed"),
+        (added, "column(s) added"),
+        (removed, "column(s) removed"),
+        (modified, "column(s) modified"),
+    ] {
+        if count > 0 {
+            parts.push(format!("{} {}", count, what));
+        }
+    }
+    if parts.is_empty() {
+        parts.push("table properties changed".to_string());
+    }
+    parts.join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{ColumnInfo, TableInfo};
+    use crate::storage::YamlStorage;
+
+    fn metadata(descriptions: &[Option<&str>]) -> TableMetadata {
+        TableMetadata {
+            table: TableInfo { name: "t".to_string(), ..Default::default() },
+            columns: descriptions.iter().enumerate()
+                .map(|(i, description)| ColumnInfo {
+                    name: format!("c{}", i),
+                    column_type: "STRING".to_string(),
+                    description: description.map(str::to_string),
+                    ..Default::default()
+                })
+                .collect(),
+        }
+    }
+
+    fn entry(action: JournalAction, target: Option<usize>, before: Option<TableMetadata>, after: Option<TableMetadata>) -> JournalEntry {
+        JournalEntry {
+            format_version: FORMAT_VERSION,
+            time: 0,
+            action,
+            target,
+            project_id: "p".to_string(),
+            dataset_id: "d".to_string(),
+            table_name: "t".to_string(),
+            before,
+            before_unreadable: false,
+            after,
+        }
+    }
+
+    #[test]
+    fn test_replay_and_summary() {
+        let described = metadata(&[Some("id"), Some("name")]);
+        let wiped = metadata(&[None, None, None]);
+        let entries = vec![
+            entry(JournalAction::Save, None, None, Some(described.clone())),
+            entry(JournalAction::Save, None, Some(described.clone()), Some(wiped.clone())),
+            entry(JournalAction::Undo, Some(2), Some(wiped.clone()), Some(described.clone())),
+        ];
+        assert_eq!(replay(&entries), (vec![1], vec![2]));
+        assert_eq!(replay(&entries[..2]), (vec![1, 2], vec![]));
+
+        // A new save after an undo discards the redo stack
+        let mut with_save = entries.clone();
+        with_save.push(entry(JournalAction::Save, None, Some(described.clone()), Some(wiped.clone())));
+        assert_eq!(replay(&with_save), (vec![1, 4], vec![]));
+
+        assert_eq!(summarize(&entries[0]), "created");
+        assert_eq!(summarize(&entries[1]), "2 column description(s) changed, 1 column(s) added");
+        assert_eq!(summarize(&entries[2]), "reverted #2");
+
+        let replaced = JournalEntry { before_unreadable: true, ..entries[0].clone() };
+        assert_eq!(summarize(&replaced), "replaced an unreadable table");
+    }
+
+    #[test]
+    fn test_restore_checks_current_state() {
+        let dir = std::env::temp_dir().join(format!("bq-meta-journal-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let storage = YamlStorage::new(dir.clone());
+        let mut described = metadata(&[Some("id")]);
+        described.table.project_id = "p".to_string();
+        described.table.dataset_id = "d".to_string();
+        let mut edited = described.clone();
+        edited.columns[0].description = Some("edited by hand".to_string());
+        let save = entry(JournalAction::Save, None, None, Some(described.clone()));
+
+        // The table was edited after the save, so undoing it would lose that edit
+        storage.save(&edited).unwrap();
+        assert!(restore(&storage, &save, save.after.as_ref(), None, false).is_err());
+        assert!(storage.exists("p", "d", "t").unwrap());
+        assert_eq!(restore(&storage, &save, save.after.as_ref(), None, true).unwrap(), (Some(edited), false));
+        assert!(!storage.exists("p", "d", "t").unwrap());
+
+        // Redo expects the table to still be missing
+        assert_eq!(restore(&storage, &save, None, save.after.as_ref(), false).unwrap(), (None, false));
+        assert_eq!(storage.load("p", "d", "t").unwrap(), described);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_torn_last_line() {
+        let dir = std::env::temp_dir().join(format!("bq-meta-journal-torn-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("journal.jsonl");
+        let line = |entry: &JournalEntry| serde_json::to_string(entry).unwrap() + "\n";
+        let save = entry(JournalAction::Save, None, None, Some(metadata(&[Some("id")])));
+
+        // An append cut short is ignored when reading and dropped by the next append
+        fs::write(&path, line(&save) + &line(&save)[..20]).unwrap();
+        assert_eq!(parse_journal(&fs::read_to_string(&path).unwrap(), &path).unwrap().len(), 1);
+        append_line(&path, &line(&save)).unwrap();
+        let content = fs::read_to_string(&path).unwrap();
+        assert_eq!(content, line(&save) + &line(&save));
+        assert_eq!(parse_journal(&content, &path).unwrap().len(), 2);
+
+        // Corruption elsewhere is still an error
+        assert!(parse_journal(&(line(&save)[..20].to_string() + "\n" + &line(&save)), &path).is_err());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
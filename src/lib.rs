@@ -2,7 +2,25 @@ pub mod models;
 pub mod config;
 pub mod storage;
 pub mod search;
+pub mod query;
+pub mod index;
 pub mod cli;
+pub mod bigquery;
+pub mod ddl;
+pub mod plan;
+pub mod sync;
+pub mod drift;
+pub mod coverage;
+pub mod lint;
+pub mod docs;
+pub mod html;
+pub mod server;
+pub mod mcp;
+pub mod lsp;
+pub mod tui;
+pub mod history;
+pub mod journal;
+pub mod migration;
 
 pub use models::*;
 pub use config::*;
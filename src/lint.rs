@@ -0,0 +1,319 @@
+use anyhow::Result;
+use regex::Regex;
+use serde::Serialize;
+use std::collections::HashSet;
+
+use crate::models::{ColumnInfo, LintConfig, Severity, TableMetadata};
+use crate::storage::Storage;
+
+/// Type names accepted by BigQuery, including legacy aliases
+pub const BIGQUERY_TYPES: &[&str] = &[
+    "STRING", "BYTES", "INTEGER", "INT64", "FLOAT", "FLOAT64", "NUMERIC", "BIGNUMERIC",
+    "DECIMAL", "BIGDECIMAL", "BOOLEAN", "BOOL", "TIMESTAMP", "DATE", "TIME", "DATETIME",
+    "INTERVAL", "GEOGRAPHY", "JSON", "RECORD", "STRUCT", "ARRAY", "RANGE",
+];
+
+/// Column modes accepted by BigQuery
+pub const BIGQUERY_MODES: &[&str] = &["NULLABLE", "REQUIRED", "REPEATED"];
+
+/// Whether a type name is a BigQuery type, allowing parameters such as `NUMERIC(10, 2)` or `ARRAY<STRING>`
+pub fn is_valid_type(column_type: &str) -> bool {
+    let base = column_type
+        .split(['(', '<'])
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_uppercase();
+    BIGQUERY_TYPES.contains(&base.as_str())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rule {
+    MissingTableDescription,
+    MissingColumnDescription,
+    ShortDescription,
+    DescriptionEqualsName,
+    DuplicateColumn,
+    InvalidType,
+    SnakeCase,
+    TrailingWhitespace,
+}
+
+impl Rule {
+    pub const ALL: [Rule; 8] = [
+        Rule::MissingTableDescription,
+        Rule::MissingColumnDescription,
+        Rule::ShortDescription,
+        Rule::DescriptionEqualsName,
+        Rule::DuplicateColumn,
+        Rule::InvalidType,
+        Rule::SnakeCase,
+        Rule::TrailingWhitespace,
+    ];
+
+    pub fn id(&self) -> &'static str {
+        match self {
+            Rule::MissingTableDescription => "missing-table-description",
+            Rule::MissingColumnDescription => "missing-column-description",
+            Rule::ShortDescription => "short-description",
+            Rule::DescriptionEqualsName => "description-equals-name",
+            Rule::DuplicateColumn => "duplicate-column",
+            Rule::InvalidType => "invalid-type",
+            Rule::SnakeCase => "snake-case",
+            Rule::TrailingWhitespace => "trailing-whitespace",
+        }
+    }
+
+    pub fn summary(&self) -> &'static str {
+        match self {
+            Rule::MissingTableDescription => "Table has no description",
+            Rule::MissingColumnDescription => "Column has no description",
+            Rule::ShortDescription => "Description is shorter than the configured minimum length",
+            Rule::DescriptionEqualsName => "Description only repeats the column name",
+            Rule::DuplicateColumn => "Column name is used more than once",
+            Rule::InvalidType => "Column type is not a BigQuery type",
+            Rule::SnakeCase => "Name is not snake_case",
+            Rule::TrailingWhitespace => "Description has trailing whitespace",
+        }
+    }
+
+    pub fn default_severity(&self) -> Severity {
+        match self {
+            Rule::DuplicateColumn | Rule::InvalidType => Severity::Error,
+            Rule::MissingTableDescription
+            | Rule::MissingColumnDescription
+            | Rule::DescriptionEqualsName
+            | Rule::SnakeCase => Severity::Warning,
+            Rule::ShortDescription | Rule::TrailingWhitespace => Severity::Info,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Diagnostic {
+    pub rule: &'static str,
+    pub severity: Severity,
+    pub table_path: String,
+    /// Dotted column path, `None` for table-level findings
+    pub column: Option<String>,
+    pub message: String,
+    /// Metadata file the finding belongs to
+    pub file: String,
+}
+
+/// Lints tables with the rule settings from `config.yaml`
+pub struct Linter {
+    config: LintConfig,
+    snake_case: Regex,
+}
+
+impl Linter {
+    /// Create a linter, rejecting unknown rule ids in the configuration
+    pub fn new(config: &LintConfig) -> Result<Self> {
+        for id in config.rules.keys() {
+            if !Rule::ALL.iter().any(|r| r.id() == id) {
+                return Err(anyhow::anyhow!("Unknown lint rule in config: {}", id));
+            }
+        }
+
+        Ok(Self {
+            config: config.clone(),
+            snake_case: Regex::new(r"^[a-z_][a-z0-9_]*$")?,
+        })
+    }
+
+    fn severity(&self, rule: Rule) -> Option<Severity> {
+        match self.config.rules.get(rule.id()) {
+            Some(rule_config) if !rule_config.enabled => None,
+            Some(rule_config) => Some(rule_config.severity.unwrap_or(rule.default_severity())),
+            None => Some(rule.default_severity()),
+        }
+    }
+
+    /// Lint a single table
+    pub fn lint_table(&self, metadata: &TableMetadata, file: &str) -> Vec<Diagnostic> {
+        let table_path = format!("{}.{}.{}", metadata.table.project_id, metadata.table.dataset_id, metadata.table.name);
+        let mut diagnostics = Vec::new();
+        let mut report = |rule: Rule, column: Option<&str>, message: String| {
+            if let Some(severity) = self.severity(rule) {
+                diagnostics.push(Diagnostic {
+                    rule: rule.id(),
+                    severity,
+                    table_path: table_path.clone(),
+                    column: column.map(str::to_string),
+                    message,
+                    file: file.to_string(),
+                });
+            }
+        };
+
+        match metadata.table.description.as_deref() {
+            Some(desc) if !desc.trim().is_empty() => {
+                self.check_description(desc, &metadata.table.name, None, &mut report);
+            }
+            _ => report(Rule::MissingTableDescription, None, "Table has no description".to_string()),
+        }
+        if !self.snake_case.is_match(&metadata.table.name) {
+            report(Rule::SnakeCase, None, format!("Table name '{}' is not snake_case", metadata.table.name));
+        }
+
+        self.lint_columns(None, &metadata.columns, &mut report);
+        diagnostics
+    }
+
+    fn lint_columns(&self, prefix: Option<&str>, columns: &[ColumnInfo], report: &mut impl FnMut(Rule, Option<&str>, String)) {
+        // BigQuery column names are case-insensitive
+        let mut seen = HashSet::new();
+
+        for column in columns.iter().filter(|c| !c.removed) {
+            let path = match prefix {
+                Some(p) => format!("{}.{}", p, column.name),
+                None => column.name.clone(),
+            };
+
+            if !seen.insert(column.name.to_lowercase()) {
+                report(Rule::DuplicateColumn, Some(&path), format!("Duplicate column name '{}'", column.name));
+            }
+            if !is_valid_type(&column.column_type) {
+                report(Rule::InvalidType, Some(&path), format!("Invalid BigQuery type '{}'", column.column_type));
+            }
+            if !self.snake_case.is_match(&column.name) {
+                report(Rule::SnakeCase, Some(&path), format!("Column name '{}' is not snake_case", column.name));
+            }
+
+            match column.description.as_deref() {
+                Some(desc) if !desc.trim().is_empty() => {
+                    self.check_description(desc, &column.name, Some(&path), report);
+                }
+                _ => report(Rule::MissingColumnDescription, Some(&path), "Column has no description".to_string()),
+            }
+
+            self.lint_columns(Some(&path), &column.fields, report);
+        }
+    }
+
+    fn check_description(&self, desc: &str, name: &str, column: Option<&str>, report: &mut impl FnMut(Rule, Option<&str>, String)) {
+        let length = desc.trim().chars().count();
+        if length < self.config.min_description_length {
+            report(Rule::ShortDescription, column, format!(
+                "Description is {} characters, expected at least {}",
+                length, self.config.min_description_length
+            ));
+        }
+        if normalize_name(desc) == normalize_name(name) {
+            report(Rule::DescriptionEqualsName, column, format!("Description '{}' only repeats the name", desc.trim()));
+        }
+        if desc.lines().any(|line| line != line.trim_end()) || desc != desc.trim_end() {
+            report(Rule::TrailingWhitespace, column, "Description has trailing whitespace".to_string());
+        }
+    }
+
+    /// Lint the given tables, reading them from storage
+    pub fn lint_tables(&self, storage: &dyn Storage, tables: &[(String, String, String)]) -> Result<Vec<Diagnostic>> {
+        let mut diagnostics = Vec::new();
+        for (project, dataset, table_name) in tables {
+            let metadata = storage.load(project, dataset, table_name)?;
+            let file = storage.location(project, dataset, table_name)?;
+            diagnostics.extend(self.lint_table(&metadata, &file));
+        }
+        Ok(diagnostics)
+    }
+}
+
+/// Lowercase and drop separators so `user_id` equals `User ID`
+fn normalize_name(value: &str) -> String {
+    value.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Render diagnostics as a SARIF 2.1.0 log
+pub fn to_sarif(diagnostics: &[Diagnostic]) -> serde_json::Value {
+    let rules: Vec<serde_json::Value> = Rule::ALL.iter()
+        .map(|rule| serde_json::json!({
+            "id": rule.id(),
+            "shortDescription": { "text": rule.summary() },
+        }))
+        .collect();
+
+    let results: Vec<serde_json::Value> = diagnostics.iter()
+        .map(|d| {
+            let subject = match d.column {
+                Some(ref column) => format!("{} ({})", d.table_path, column),
+                None => d.table_path.clone(),
+            };
+            serde_json::json!({
+                "ruleId": d.rule,
+                "level": match d.severity {
+                    Severity::Error => "error",
+                    Severity::Warning => "warning",
+                    Severity::Info => "note",
+                },
+                "message": { "text": format!("{}: {}", subject, d.message) },
+                "locations": [{
+                    "physicalLocation": { "artifactLocation": { "uri": d.file } },
+                }],
+            })
+        })
+        .collect();
+
+    serde_json::json!({
+        "version": "2.1.0",
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "bq-meta",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "rules": rules,
+                },
+            },
+            "results": results,
+        }],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::LintRuleConfig;
+
+    #[test]
+    fn test_lint_table() {
+        let metadata: TableMetadata = serde_yaml::from_str(r#"
+table: {name: UserEvents, project_id: p, dataset_id: d}
+columns:
+  - {name: user_id, type: STRING, description: "User ID"}
+  - {name: User_Id, type: STRNG, description: "Identifier of the user "}
+  - {name: amount, type: "NUMERIC(10, 2)", description: Short}
+  - name: items
+    type: ARRAY<STRUCT<id STRING>>
+    description: Purchased items in the order
+    fields: [{name: id, type: STRING}]
+"#).unwrap();
+
+        let mut config = LintConfig::default();
+        config.rules.insert("snake-case".to_string(), LintRuleConfig { enabled: false, severity: None });
+        config.rules.insert("short-description".to_string(), LintRuleConfig { enabled: true, severity: Some(Severity::Error) });
+        let linter = Linter::new(&config).unwrap();
+
+        let diagnostics = linter.lint_table(&metadata, "t.yaml");
+        let found: Vec<(&str, Option<&str>, Severity)> = diagnostics.iter()
+            .map(|d| (d.rule, d.column.as_deref(), d.severity))
+            .collect();
+        assert_eq!(found, vec![
+            ("missing-table-description", None, Severity::Warning),
+            ("short-description", Some("user_id"), Severity::Error),
+            ("description-equals-name", Some("user_id"), Severity::Warning),
+            ("duplicate-column", Some("User_Id"), Severity::Error),
+            ("invalid-type", Some("User_Id"), Severity::Error),
+            ("trailing-whitespace", Some("User_Id"), Severity::Info),
+            ("short-description", Some("amount"), Severity::Error),
+            ("missing-column-description", Some("items.id"), Severity::Warning),
+        ]);
+
+        config.rules.insert("no-such-rule".to_string(), LintRuleConfig { enabled: true, severity: None });
+        assert!(Linter::new(&config).is_err());
+    }
+}
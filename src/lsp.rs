@@ -0,0 +1,477 @@
+use anyhow::Result;
+use lsp_server::{Connection, Message, Notification, Request, RequestId, Response};
+use lsp_types::notification::{DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument, Notification as _, PublishDiagnostics};
+use lsp_types::request::{Completion, HoverRequest, Request as _};
+use lsp_types::{
+    CompletionItem, CompletionItemKind, CompletionOptions, CompletionParams, CompletionResponse, Diagnostic,
+    DiagnosticSeverity, DidChangeTextDocumentParams, DidCloseTextDocumentParams, DidOpenTextDocumentParams, Hover,
+    HoverContents, HoverParams, HoverProviderCapability, MarkupContent, MarkupKind, Position, PublishDiagnosticsParams,
+    Range, ServerCapabilities, TextDocumentSyncCapability, TextDocumentSyncKind, Url,
+};
+use regex::Regex;
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+
+use crate::lint::{Linter, Rule, BIGQUERY_MODES, BIGQUERY_TYPES};
+use crate::migration;
+use crate::models::{LintConfig, LintRuleConfig, Severity, TableMetadata};
+use crate::storage::open_storage;
+
+/// Length of a string in UTF-16 code units, as LSP positions count by default
+fn utf16_len(text: &str) -> u32 {
+    text.encode_utf16().count() as u32
+}
+
+fn line_range(line: u32, start: u32, end: u32) -> Range {
+    Range::new(Position::new(line, start), Position::new(line, end))
+}
+
+/// Find `key: value` starting at line `from`, returning the line index, the range of the value
+/// and the unquoted value. Handles both block (`name: foo`) and flow (`{name: foo}`) style.
+pub(crate) fn find_value(lines: &[&str], from: usize, key: &str, value: Option<&str>) -> Option<(usize, Range, String)> {
+    let needle = format!("{}:", key);
+    for (index, line) in lines.iter().enumerate().skip(from) {
+        let mut offset = 0;
+        while let Some(found) = line[offset..].find(&needle) {
+            let key_start = offset + found;
+            offset = key_start + needle.len();
+
+            let preceded_ok = line[..key_start].chars().next_back()
+                .is_none_or(|c| c.is_whitespace() || matches!(c, '{' | ',' | '-'));
+            if !preceded_ok {
+                continue;
+            }
+
+            let rest = &line[offset..];
+            let value_start = offset + (rest.len() - rest.trim_start().len());
+            let value_end = line[value_start..].find([',', '}', '#'])
+                .map_or(line.len(), |i| value_start + i);
+            let raw = line[value_start..value_end].trim_end();
+            let found_value = raw.trim_matches(|c| c == '"' || c == '\'');
+
+            if value.is_none_or(|v| v == found_value) {
+                let range = line_range(
+                    index as u32,
+                    utf16_len(&line[..value_start]),
+                    utf16_len(&line[..value_start + raw.len()]),
+                );
+                return Some((index, range, found_value.to_string()));
+            }
+        }
+    }
+    None
+}
+
+fn indent(line: &str) -> usize {
+    line.len() - line.trim_start().len()
+}
+
+/// Lines `start..end` of the list item whose `name` key is on line `name_line`: up to the next
+/// non-blank line at or left of the item's `-`
+fn column_block(lines: &[&str], name_line: usize) -> (usize, usize) {
+    let key_column = lines[name_line].find("name:").unwrap_or(0);
+    let start = (0..=name_line).rev()
+        .find(|&i| lines[i].trim_start().starts_with('-') && indent(lines[i]) < key_column)
+        .unwrap_or(name_line);
+    let dash = indent(lines[start]);
+    let end = (name_line + 1..lines.len())
+        .find(|&i| !lines[i].trim().is_empty() && indent(lines[i]) <= dash)
+        .unwrap_or(lines.len());
+    (start, end)
+}
+
+/// Locate the value of `key` for the column at a dotted path. Only the column's own keys are
+/// considered, not those of later columns or of its nested fields.
+pub(crate) fn locate_column(lines: &[&str], path: &str, key: &str) -> Option<Range> {
+    let mut line = lines.iter().position(|l| l.trim_start().starts_with("columns:")).unwrap_or(0);
+    for part in path.split('.') {
+        line = find_value(lines, line, "name", Some(part))?.0;
+    }
+    if key == "name" {
+        return find_value(lines, line, "name", path.rsplit('.').next()).map(|(_, r, _)| r);
+    }
+
+    let (start, end) = column_block(lines, line);
+    // Keys of the column itself sit on its `-` line or at the indent of its `name` key
+    let key_column = lines[line].find("name:").unwrap_or(0);
+    let own_key = |index: usize| {
+        index == start
+            || (indent(lines[index]) == key_column && lines[index].trim_start().starts_with(&format!("{}:", key)))
+    };
+    let mut from = start;
+    while let Some((found, range, _)) = find_value(lines, from, key, None) {
+        if found >= end {
+            return None;
+        }
+        if own_key(found) {
+            return Some(range);
+        }
+        from = found + 1;
+    }
+    None
+}
+
+fn diagnostic(range: Range, severity: DiagnosticSeverity, code: &str, message: String) -> Diagnostic {
+    Diagnostic {
+        range,
+        severity: Some(severity),
+        code: Some(lsp_types::NumberOrString::String(code.to_string())),
+        source: Some("bq-meta".to_string()),
+        message,
+        ..Default::default()
+    }
+}
+
+/// Diagnostics for a metadata YAML document; `path` enables the filename checks
+pub fn diagnose(text: &str, path: Option<&Path>) -> Vec<Diagnostic> {
+    let lines: Vec<&str> = text.lines().collect();
+
+    let metadata: TableMetadata = match serde_yaml::from_str(text) {
+        Ok(metadata) => metadata,
+        Err(err) => {
+            let range = match err.location() {
+                Some(location) => {
+                    let line = location.line().saturating_sub(1);
+                    let length = lines.get(line).map_or(0, |l| utf16_len(l));
+                    line_range(line as u32, 0, length)
+                }
+                None => line_range(0, 0, 0),
+            };
+            return vec![diagnostic(range, DiagnosticSeverity::ERROR, "parse-error", err.to_string())];
+        }
+    };
+
+    let mut diagnostics = Vec::new();
+
+    if let Err(err) = migration::document_version(text) {
+        let range = find_value(&lines, 0, "format_version", None).map_or(line_range(0, 0, 0), |(_, r, _)| r);
+        diagnostics.push(diagnostic(range, DiagnosticSeverity::ERROR, "format-version", format!("{:#}", err)));
+    }
+
+    // Only the structural lint rules make sense while editing
+    let rules: BTreeMap<String, LintRuleConfig> = Rule::ALL.iter()
+        .map(|rule| (rule.id().to_string(), LintRuleConfig {
+            enabled: matches!(rule, Rule::InvalidType | Rule::DuplicateColumn),
+            severity: None,
+        }))
+        .collect();
+    let config = LintConfig { rules, ..Default::default() };
+    if let Ok(linter) = Linter::new(&config) {
+        for finding in linter.lint_table(&metadata, "") {
+            let Some(ref column) = finding.column else { continue };
+            let range = if finding.rule == Rule::InvalidType.id() {
+                locate_column(&lines, column, "type")
+            } else {
+                // Point at the second occurrence of the duplicated name
+                locate_column(&lines, column, "name").and_then(|first| {
+                    let name = column.rsplit('.').next();
+                    find_value(&lines, first.start.line as usize + 1, "name", name).map(|(_, r, _)| r)
+                })
+            }.unwrap_or_else(|| line_range(0, 0, 0));
+            let severity = match finding.severity {
+                Severity::Error => DiagnosticSeverity::ERROR,
+                Severity::Warning => DiagnosticSeverity::WARNING,
+                Severity::Info => DiagnosticSeverity::INFORMATION,
+            };
+            diagnostics.push(diagnostic(range, severity, finding.rule, finding.message));
+        }
+    }
+
+    if let Some(path) = path {
+        let table_line = lines.iter().position(|l| l.trim_start().starts_with("table:")).unwrap_or(0);
+        let mut check = |key: &str, expected: Option<&str>, actual: &str, what: &str| {
+            if let Some(expected) = expected {
+                if expected != actual {
+                    let range = find_value(&lines, table_line, key, Some(actual))
+                        .map_or(line_range(0, 0, 0), |(_, r, _)| r);
+                    diagnostics.push(diagnostic(range, DiagnosticSeverity::ERROR, "path-mismatch", format!(
+                        "table.{} '{}' does not match the {} '{}'", key, actual, what, expected
+                    )));
+                }
+            }
+        };
+
+        let name = |p: Option<&Path>| p.and_then(|p| p.file_name()).and_then(|n| n.to_str()).map(str::to_string);
+        let file_stem = path.file_stem().and_then(|s| s.to_str());
+        let dataset_dir = name(path.parent());
+        let project_dir = name(path.parent().and_then(Path::parent));
+        check("name", file_stem, &metadata.table.name, "file name");
+        check("dataset_id", dataset_dir.as_deref(), &metadata.table.dataset_id, "dataset directory");
+        check("project_id", project_dir.as_deref(), &metadata.table.project_id, "project directory");
+    }
+
+    diagnostics
+}
+
+/// Completion items for the text before the cursor on the current line
+pub fn complete(line_prefix: &str) -> Vec<CompletionItem> {
+    let Ok(re) = Regex::new(r#"(?:^|[\s{,-])(type|mode):\s*["']?(\w*)$"#) else { return Vec::new() };
+    let Some(captures) = re.captures(line_prefix) else { return Vec::new() };
+
+    let (candidates, detail) = match &captures[1] {
+        "type" => (BIGQUERY_TYPES, "BigQuery type"),
+        _ => (BIGQUERY_MODES, "BigQuery column mode"),
+    };
+    let typed = captures[2].to_uppercase();
+    candidates.iter()
+        .filter(|c| c.starts_with(&typed))
+        .map(|c| CompletionItem {
+            label: c.to_string(),
+            kind: Some(CompletionItemKind::ENUM_MEMBER),
+            detail: Some(detail.to_string()),
+            ..Default::default()
+        })
+        .collect()
+}
+
+/// Column name under the cursor, if the line is a column `name:` entry
+fn column_name_at(text: &str, position: Position) -> Option<String> {
+    let lines: Vec<&str> = text.lines().collect();
+    let line = position.line as usize;
+    let columns_line = lines.iter().position(|l| l.trim_start().starts_with("columns:"))?;
+    if line <= columns_line {
+        return None;
+    }
+
+    let (_, range, value) = find_value(&lines[..=line], line, "name", None)?;
+    (range.start.character <= position.character && position.character <= range.end.character)
+        .then_some(value)
+}
+
+/// Markdown listing descriptions of same-named columns in other tables
+fn hover_for_column(column_name: &str, current: Option<&TableMetadata>) -> Result<Option<String>> {
+    const MAX_ENTRIES: usize = 10;
+    let current_path = current.map(|m| format!("{}.{}.{}", m.table.project_id, m.table.dataset_id, m.table.name));
+    let mut entries = Vec::new();
+
+    let storage = open_storage()?;
+    for (project, dataset, table) in storage.list(None, None)? {
+        let table_path = format!("{}.{}.{}", project, dataset, table);
+        if Some(&table_path) == current_path.as_ref() {
+            continue;
+        }
+        let Ok(metadata) = storage.load(&project, &dataset, &table) else { continue };
+        for (path, column) in metadata.flatten_columns() {
+            if column.name == column_name {
+                if let Some(ref desc) = column.description {
+                    entries.push(format!("- `{}` `{}` ({}): {}", table_path, path, column.column_type, desc));
+                }
+            }
+        }
+        if entries.len() >= MAX_ENTRIES {
+            break;
+        }
+    }
+
+    if entries.is_empty() {
+        return Ok(None);
+    }
+    entries.truncate(MAX_ENTRIES);
+    Ok(Some(format!("**{}** in other tables:\n\n{}", column_name, entries.join("\n"))))
+}
+
+fn publish_diagnostics(connection: &Connection, uri: &Url, text: &str) -> Result<()> {
+    let path = uri.to_file_path().ok();
+    let params = PublishDiagnosticsParams {
+        uri: uri.clone(),
+        diagnostics: diagnose(text, path.as_deref()),
+        version: None,
+    };
+    connection.sender.send(Message::Notification(Notification::new(PublishDiagnostics::METHOD.to_string(), params)))?;
+    Ok(())
+}
+
+/// Deserialize request params, answering `InvalidParams` when they don't fit
+fn parse_params<P: serde::de::DeserializeOwned>(id: &RequestId, params: serde_json::Value) -> std::result::Result<P, Response> {
+    serde_json::from_value(params).map_err(|e| Response::new_err(
+        id.clone(),
+        lsp_server::ErrorCode::InvalidParams as i32,
+        format!("Invalid params: {}", e),
+    ))
+}
+
+/// Answer a request; failures become error responses so one bad request doesn't stop the server
+fn handle_request(request: Request, documents: &HashMap<Url, String>) -> Response {
+    match request.method.as_str() {
+        Completion::METHOD => {
+            let params: CompletionParams = match parse_params(&request.id, request.params) {
+                Ok(params) => params,
+                Err(response) => return response,
+            };
+            let position = params.text_document_position.position;
+            let items = documents.get(&params.text_document_position.text_document.uri)
+                .and_then(|text| text.lines().nth(position.line as usize))
+                .map(|line| {
+                    let prefix: Vec<u16> = line.encode_utf16().take(position.character as usize).collect();
+                    complete(&String::from_utf16_lossy(&prefix))
+                })
+                .unwrap_or_default();
+            Response::new_ok(request.id, CompletionResponse::Array(items))
+        }
+        HoverRequest::METHOD => {
+            let params: HoverParams = match parse_params(&request.id, request.params) {
+                Ok(params) => params,
+                Err(response) => return response,
+            };
+            let position = params.text_document_position_params.position;
+            let name = documents.get(&params.text_document_position_params.text_document.uri)
+                .and_then(|text| column_name_at(text, position).map(|name| (name, text)));
+            let hover = match name {
+                Some((name, text)) => {
+                    let current: Option<TableMetadata> = serde_yaml::from_str(text).ok();
+                    match hover_for_column(&name, current.as_ref()) {
+                        Ok(value) => value.map(|value| Hover {
+                            contents: HoverContents::Markup(MarkupContent { kind: MarkupKind::Markdown, value }),
+                            range: None,
+                        }),
+                        Err(err) => return Response::new_err(
+                            request.id,
+                            lsp_server::ErrorCode::InternalError as i32,
+                            format!("{:#}", err),
+                        ),
+                    }
+                }
+                None => None,
+            };
+            Response::new_ok(request.id, hover)
+        }
+        _ => Response::new_err(
+            request.id,
+            lsp_server::ErrorCode::MethodNotFound as i32,
+            format!("Method not found: {}", request.method),
+        ),
+    }
+}
+
+fn handle_notification(connection: &Connection, notification: Notification, documents: &mut HashMap<Url, String>) -> Result<()> {
+    match notification.method.as_str() {
+        DidOpenTextDocument::METHOD => {
+            let params: DidOpenTextDocumentParams = serde_json::from_value(notification.params)?;
+            publish_diagnostics(connection, &params.text_document.uri, &params.text_document.text)?;
+            documents.insert(params.text_document.uri, params.text_document.text);
+        }
+        DidChangeTextDocument::METHOD => {
+            let params: DidChangeTextDocumentParams = serde_json::from_value(notification.params)?;
+            // Full document sync: the last change holds the whole text
+            if let Some(change) = params.content_changes.into_iter().last() {
+                publish_diagnostics(connection, &params.text_document.uri, &change.text)?;
+                documents.insert(params.text_document.uri, change.text);
+            }
+        }
+        DidCloseTextDocument::METHOD => {
+            let params: DidCloseTextDocumentParams = serde_json::from_value(notification.params)?;
+            documents.remove(&params.text_document.uri);
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Run the language server over stdin/stdout
+pub fn run_stdio() -> Result<()> {
+    let (connection, io_threads) = Connection::stdio();
+
+    let capabilities = ServerCapabilities {
+        text_document_sync: Some(TextDocumentSyncCapability::Kind(TextDocumentSyncKind::FULL)),
+        completion_provider: Some(CompletionOptions {
+            trigger_characters: Some(vec![":".to_string(), " ".to_string()]),
+            ..Default::default()
+        }),
+        hover_provider: Some(HoverProviderCapability::Simple(true)),
+        ..Default::default()
+    };
+    connection.initialize(serde_json::to_value(capabilities)?)?;
+
+    let mut documents: HashMap<Url, String> = HashMap::new();
+    for message in &connection.receiver {
+        match message {
+            Message::Request(request) => {
+                if connection.handle_shutdown(&request)? {
+                    break;
+                }
+                let response = handle_request(request, &documents);
+                connection.sender.send(Message::Response(response))?;
+            }
+            Message::Notification(notification) => {
+                // Notifications can't be answered, so a malformed one is logged (stdout is the
+                // protocol channel) and skipped
+                let method = notification.method.clone();
+                if let Err(err) = handle_notification(&connection, notification, &mut documents) {
+                    eprintln!("bq-meta lsp: ignoring {} notification: {:#}", method, err);
+                }
+            }
+            Message::Response(_) => {}
+        }
+    }
+
+    // The reader thread only finishes once the connection's channels are gone
+    drop(connection);
+    io_threads.join()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diagnose() {
+        let text = "table:\n  name: orders\n  project_id: p\n  dataset_id: d\ncolumns:\n  - name: id\n    type: STRNG\n  - {name: id, type: STRING}\n";
+        let diagnostics = diagnose(text, Some(Path::new("/data/p/d/order.yaml")));
+        let found: Vec<(&str, u32, u32)> = diagnostics.iter()
+            .map(|d| match d.code {
+                Some(lsp_types::NumberOrString::String(ref code)) => (code.as_str(), d.range.start.line, d.range.start.character),
+                _ => ("", 0, 0),
+            })
+            .collect();
+        assert_eq!(found, vec![
+            ("invalid-type", 6, 10),
+            ("duplicate-column", 7, 11),
+            ("path-mismatch", 1, 8),
+        ]);
+
+        let broken = diagnose("table:\n  name: [unclosed\n", None);
+        assert_eq!(broken[0].code, Some(lsp_types::NumberOrString::String("parse-error".to_string())));
+    }
+
+    #[test]
+    fn test_locate_column() {
+        let text = "columns:\n\
+                    - name: id\n  type: STRING\n\
+                    - name: user\n  type: RECORD\n  fields:\n  - name: email\n    description: Email\n\
+                    - name: status\n  description: Order status\n\
+                    - {name: total, description: Amount}\n";
+        let lines: Vec<&str> = text.lines().collect();
+        let line_of = |path: &str| locate_column(&lines, path, "description").map(|r| r.start.line);
+
+        // Undocumented columns don't pick up a nested field's or the next column's description
+        assert_eq!(line_of("id"), None);
+        assert_eq!(line_of("user"), None);
+        assert_eq!(line_of("user.email"), Some(7));
+        assert_eq!(line_of("status"), Some(9));
+        assert_eq!(line_of("total"), Some(10));
+    }
+
+    #[test]
+    fn test_complete() {
+        let labels = |prefix: &str| -> Vec<String> { complete(prefix).into_iter().map(|c| c.label).collect() };
+        assert_eq!(labels("    type: INT6"), vec!["INT64"]);
+        assert_eq!(labels("    type: "), BIGQUERY_TYPES.to_vec());
+        assert_eq!(labels("  - {name: x, mode: r"), vec!["REQUIRED", "REPEATED"]);
+        assert!(labels("    description: ty").is_empty());
+    }
+
+    #[test]
+    fn test_column_name_at() {
+        let text = "table:\n  name: orders\ncolumns:\n  - name: user_id\n    type: STRING\n";
+        assert_eq!(column_name_at(text, Position::new(3, 12)), Some("user_id".to_string()));
+        assert_eq!(column_name_at(text, Position::new(1, 9)), None);
+    }
+
+    #[test]
+    fn test_invalid_params() {
+        let request = Request::new(RequestId::from(1), Completion::METHOD.to_string(), serde_json::json!({"bogus": true}));
+        let response = handle_request(request, &HashMap::new());
+        assert_eq!(response.error.unwrap().code, lsp_server::ErrorCode::InvalidParams as i32);
+    }
+}
@@ -1,4 +1,4 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 use clap::Parser;
 use colored::*;
 use std::io::{self, Write};
@@ -10,19 +10,23 @@ async fn main() -> Result<()> {
     let cli = Cli::parse();
     
     match cli.command {
-        Commands::Init { path } => {
+        Commands::Init { path, git } => {
             if let Some(custom_path) = path {
                 std::env::set_var("BQ_META_PATH", custom_path);
             }
             init_data_directory()?;
+            if git {
+                let repo = history::init_repository()?;
+                println!("Tracking changes in git repository: {}", repo.display());
+            }
         }
         Commands::List { project, dataset, output } => {
-            let tables = list_tables(project.as_deref(), dataset.as_deref())?;
+            let tables = open_storage()?.list(project.as_deref(), dataset.as_deref())?;
             display_table_list(&tables, &output)?;
         }
         Commands::Search { 
             pattern, all, desc, column, col_desc, regex, case_sensitive, 
-            project, dataset, output 
+            fuzzy, threshold, query, project, dataset, limit, output 
         } => {
             let options = SearchOptions {
                 pattern,
@@ -34,39 +38,193 @@ async fn main() -> Result<()> {
                 search_column_desc: col_desc,
                 project_filter: project,
                 dataset_filter: dataset,
+                limit,
+                fuzzy: fuzzy.then_some(threshold),
+                query,
             };
-            let results = search_tables(&options)?;
-            display_search_results(&results, &output)?;
+            let outcome = search_tables(&options)?;
+            display_search_results(&outcome.results, &output)?;
+            if !outcome.newer_format.is_empty() {
+                eprintln!("{} {} table(s) written by a newer bq-meta were not searched: {}",
+                          "Warning:".yellow(), outcome.newer_format.len(), outcome.newer_format.join(", "));
+            }
         }
         Commands::Show { table, output } => {
             let (project, dataset, table_name) = parse_table_spec(&table)
                 .map_err(|e| anyhow::anyhow!(e))?;
-            let metadata = load_table_metadata(&project, &dataset, &table_name)?;
+            let metadata = open_storage()?.load(&project, &dataset, &table_name)?;
             display_table_metadata(&metadata, &output)?;
         }
         Commands::Describe { table, output } => {
             let (project, dataset, table_name) = parse_table_spec(&table)
                 .map_err(|e| anyhow::anyhow!(e))?;
-            let metadata = load_table_metadata(&project, &dataset, &table_name)?;
+            let metadata = open_storage()?.load(&project, &dataset, &table_name)?;
             display_column_descriptions(&metadata, &output)?;
         }
         Commands::Create { table, description } => {
             let (project, dataset, table_name) = parse_table_spec(&table)
                 .map_err(|e| anyhow::anyhow!(e))?;
-            create_table_metadata(&project, &dataset, &table_name, description)?;
+            create_table_metadata(&*open_storage()?, &project, &dataset, &table_name, description)?;
         }
         Commands::Edit { table, column, description } => {
             let (project, dataset, table_name) = parse_table_spec(&table)
                 .map_err(|e| anyhow::anyhow!(e))?;
-            edit_column_description(&project, &dataset, &table_name, &column, description)?;
+            edit_column_description(&*open_storage()?, &project, &dataset, &table_name, &column, description)?;
         }
         Commands::Export { table, file, format } => {
             let (project, dataset, table_name) = parse_table_spec(&table)
                 .map_err(|e| anyhow::anyhow!(e))?;
-            export_table_metadata(&project, &dataset, &table_name, file.as_deref(), &format)?;
+            export_table_metadata(&*open_storage()?, &project, &dataset, &table_name, file.as_deref(), &format)?;
+        }
+        Commands::Import { file, force, schema, resource } => {
+            let storage = open_storage()?;
+            if resource {
+                import_bigquery_resources(&*storage, &file, force)?;
+            } else if let Some(table) = schema {
+                let (project, dataset, table_name) = parse_table_spec(&table)
+                    .map_err(|e| anyhow::anyhow!(e))?;
+                import_bigquery_schema(&*storage, &file, &project, &dataset, &table_name, force)?;
+            } else {
+                import_table_metadata(&*storage, &file, force)?;
+            }
+        }
+        Commands::Ddl { table, project, dataset, file } => {
+            let storage = open_storage()?;
+            let tables = resolve_tables(&*storage, table.as_deref(), project.as_deref(), dataset.as_deref())?;
+            export_ddl(&*storage, &tables, file.as_deref())?;
+        }
+        Commands::Plan { table, project, dataset, output } => {
+            let storage = open_storage()?;
+            let tables = resolve_tables(&*storage, table.as_deref(), project.as_deref(), dataset.as_deref())?;
+            let plans = plan_tables(&*storage, &tables).await?;
+            display_plan(&plans, &output)?;
+        }
+        Commands::Apply { table, project, dataset, yes } => {
+            let storage = open_storage()?;
+            let tables = resolve_tables(&*storage, table.as_deref(), project.as_deref(), dataset.as_deref())?;
+            apply_tables(&*storage, &tables, yes).await?;
+        }
+        Commands::Pull { table, project, dataset, output } => {
+            let storage = open_storage()?;
+            let tables = resolve_tables(&*storage, table.as_deref(), project.as_deref(), dataset.as_deref())?;
+            pull_tables(&*storage, &tables, &output).await?;
+        }
+        Commands::Drift { table, project, dataset, schemas, output } => {
+            let items = detect_drift(&*open_storage()?, table.as_deref(), project.as_deref(), dataset.as_deref(), schemas.as_deref()).await?;
+            display_drift(&items, &output)?;
+            if !items.is_empty() {
+                std::process::exit(1);
+            }
+        }
+        Commands::Coverage { project, dataset, threshold, output } => {
+            let report = coverage::compute_coverage(project.as_deref(), dataset.as_deref())?;
+            display_coverage(&report, &output)?;
+            if let Some(threshold) = threshold {
+                if report.table_percent < threshold || report.column_percent < threshold {
+                    eprintln!("Coverage is below threshold {:.1}%", threshold);
+                    std::process::exit(1);
+                }
+            }
+        }
+        Commands::Lint { table, project, dataset, output } => {
+            let storage = open_storage()?;
+            let tables = resolve_tables(&*storage, table.as_deref(), project.as_deref(), dataset.as_deref())?;
+            let linter = lint::Linter::new(&load_config()?.lint)?;
+            let diagnostics = linter.lint_tables(&*storage, &tables)?;
+            display_lint(&diagnostics, &output)?;
+            if diagnostics.iter().any(|d| d.severity == Severity::Error) {
+                std::process::exit(1);
+            }
+        }
+        Commands::Docs { out, project, dataset, format } => {
+            let catalog = docs::load_catalog(project.as_deref(), dataset.as_deref())?;
+            let out_dir = std::path::Path::new(&out);
+            let count = match format.as_str() {
+                "markdown" => docs::write_markdown_site(&catalog, out_dir)?,
+                "html" => html::write_html_site(&catalog, out_dir)?,
+                _ => return Err(anyhow::anyhow!("Unsupported format: {}", format)),
+            };
+            println!("Generated documentation for {} table(s) in: {}", count, out_dir.display());
+        }
+        Commands::Serve { addr, write } => {
+            let addr = addr.parse()
+                .with_context(|| format!("Invalid listen address: {}", addr))?;
+            server::serve(addr, write).await?;
+        }
+        Commands::Mcp => {
+            mcp::run_stdio()?;
+        }
+        Commands::Lsp => {
+            lsp::run_stdio()?;
+        }
+        Commands::Tui => {
+            tui::run()?;
+        }
+        Commands::Reindex => {
+            let mut index = index::SearchIndex::build()?;
+            index.save()?;
+            println!("Indexed {} table(s)", index.len());
+        }
+        Commands::History { table, output } => {
+            let table = table.as_deref().map(parse_table_spec).transpose()
+                .map_err(|e| anyhow::anyhow!(e))?;
+            let entries = history::history(table.as_ref().map(|(p, d, t)| (p.as_str(), d.as_str(), t.as_str())))?;
+            display_history(&entries, &output)?;
+        }
+        Commands::Blame { table, output } => {
+            let (project, dataset, table_name) = parse_table_spec(&table)
+                .map_err(|e| anyhow::anyhow!(e))?;
+            let entries = history::blame(&project, &dataset, &table_name)?;
+            display_blame(&entries, &output)?;
+        }
+        Commands::Undo { steps, force } => {
+            let entries = journal::undo(steps, force)?;
+            report_journal_step(journal::JournalAction::Undo, &entries);
+        }
+        Commands::Redo { steps, force } => {
+            let entries = journal::redo(steps, force)?;
+            report_journal_step(journal::JournalAction::Redo, &entries);
+        }
+        Commands::Log { table, limit, output } => {
+            let table = table.as_deref().map(parse_table_spec).transpose()
+                .map_err(|e| anyhow::anyhow!(e))?;
+            let entries = journal::log(table.as_ref().map(|(p, d, t)| (p.as_str(), d.as_str(), t.as_str())), limit)?;
+            display_log(&entries, &output)?;
+        }
+        Commands::Migrate { dry_run } => {
+            let report = migration::migrate(dry_run)?;
+            let verb = if dry_run { "Would migrate" } else { "Migrated" };
+            if report.config {
+                println!("{} {}", verb, get_config_path()?.display());
+            }
+            for (project, dataset, table_name) in &report.tables {
+                println!("{} {}.{}.{}", verb, project, dataset, table_name);
+            }
+            for (location, reason) in &report.failed {
+                eprintln!("{} {}: {}", "Cannot migrate".red(), location, reason);
+            }
+            if !report.config && report.tables.is_empty() && report.failed.is_empty() {
+                println!("Everything is already at format version {}", migration::FORMAT_VERSION);
+            } else {
+                println!("{} {} table(s) to format version {}", verb, report.tables.len(), migration::FORMAT_VERSION);
+            }
+            if !report.failed.is_empty() {
+                eprintln!("{} table(s) could not be migrated", report.failed.len());
+                std::process::exit(1);
+            }
         }
-        Commands::Import { file, force } => {
-            import_table_metadata(&file, force)?;
+        Commands::Storage { action } => {
+            match action {
+                StorageAction::Copy { from, to } => {
+                    let (from, to) = (parse_storage_backend(&from)?, parse_storage_backend(&to)?);
+                    if from == to {
+                        return Err(anyhow::anyhow!("Source and destination backends are the same: {}", from));
+                    }
+                    let config = load_config()?;
+                    let count = storage::copy_tables(&*storage::open_backend(&config, from)?, &*storage::open_backend(&config, to)?)?;
+                    println!("Copied {} table(s) from {} to {}", count, from, to);
+                }
+            }
         }
         Commands::Config { action } => {
             match action {
@@ -86,6 +244,13 @@ async fn main() -> Result<()> {
     Ok(())
 }
 
+fn resolve_tables(storage: &dyn Storage, table: Option<&str>, project: Option<&str>, dataset: Option<&str>) -> Result<Vec<(String, String, String)>> {
+    match table {
+        Some(spec) => Ok(vec![parse_table_spec(spec).map_err(|e| anyhow::anyhow!(e))?]),
+        None => storage.list(project, dataset),
+    }
+}
+
 fn display_table_list(tables: &[(String, String, String)], output_format: &str) -> Result<()> {
     match output_format {
         "json" => {
@@ -115,15 +280,7 @@ fn display_table_list(tables: &[(String, String, String)], output_format: &str)
 fn display_search_results(results: &[SearchResult], output_format: &str) -> Result<()> {
     match output_format {
         "json" => {
-            let json_results: Vec<serde_json::Value> = results.iter().map(|r| {
-                serde_json::json!({
-                    "table_path": r.table_path,
-                    "match_type": r.match_type.to_string(),
-                    "matched_content": r.matched_content,
-                    "context": r.context
-                })
-            }).collect();
-            println!("{}", serde_json::to_string_pretty(&json_results)?);
+            println!("{}", serde_json::to_string_pretty(results)?);
         }
         _ => {
             if results.is_empty() {
@@ -143,6 +300,9 @@ fn display_search_results(results: &[SearchResult], output_format: &str) -> Resu
                 if let Some(ref context) = result.context {
                     print!(" ({})", context.dimmed());
                 }
+                if let Some(similarity) = result.similarity.filter(|s| *s < 1.0) {
+                    print!(" {}", format!("~{:.0}%", similarity * 100.0).dimmed());
+                }
                 println!();
                 println!("  {}", result.matched_content.italic());
                 println!();
@@ -169,42 +329,77 @@ fn display_table_metadata(metadata: &TableMetadata, output_format: &str) -> Resu
             if let Some(ref desc) = metadata.table.description {
                 println!("Description: {}", desc);
             }
+            if let Some(ref table_type) = metadata.table.table_type {
+                println!("Type: {}", table_type);
+            }
+            if let Some(ref partitioning) = metadata.table.partitioning {
+                match partitioning.field {
+                    Some(ref field) => println!("Partitioning: {} ({})", partitioning.partition_type, field),
+                    None => println!("Partitioning: {} (ingestion time)", partitioning.partition_type),
+                }
+            }
+            if !metadata.table.clustering.is_empty() {
+                println!("Clustering: {}", metadata.table.clustering.join(", "));
+            }
+            if !metadata.table.labels.is_empty() {
+                let labels: Vec<String> = metadata.table.labels.iter()
+                    .map(|(k, v)| format!("{}={}", k, v))
+                    .collect();
+                println!("Labels: {}", labels.join(", "));
+            }
+            if let Some(ref query) = metadata.table.view_query {
+                println!("View Query:\n{}", query.dimmed());
+            }
             
             println!("\n{}", "Columns".bold());
             println!("{}", "─".repeat(30));
-            for column in &metadata.columns {
-                println!("  {} ({})", column.name.green(), column.column_type.blue());
-                if let Some(ref desc) = column.description {
-                    println!("    {}", desc.italic());
-                }
-            }
+            print_columns(&metadata.columns, 1);
         }
     }
     Ok(())
 }
 
+fn print_columns(columns: &[ColumnInfo], depth: usize) {
+    let indent = "  ".repeat(depth);
+    for column in columns {
+        print!("{}{} ({})", indent, column.name.green(), column.column_type.blue());
+        if column.removed {
+            print!(" {}", "[removed]".red());
+        }
+        println!();
+        if let Some(ref desc) = column.description {
+            println!("{}  {}", indent, desc.italic());
+        }
+        print_columns(&column.fields, depth + 1);
+    }
+}
+
 fn display_column_descriptions(metadata: &TableMetadata, output_format: &str) -> Result<()> {
     match output_format {
         "json" => {
-            let columns_with_desc: Vec<_> = metadata.columns.iter()
-                .map(|c| serde_json::json!({
-                    "name": c.name,
+            let columns_with_desc: Vec<_> = metadata.flatten_columns().into_iter()
+                .map(|(path, c)| serde_json::json!({
+                    "name": path,
                     "type": c.column_type,
                     "description": c.description
                 })).collect();
             println!("{}", serde_json::to_string_pretty(&columns_with_desc)?);
         }
         "yaml" => {
-            let columns_with_desc: Vec<_> = metadata.columns.iter()
-                .map(|c| (c.name.clone(), c.column_type.clone(), c.description.clone()))
+            let columns_with_desc: Vec<_> = metadata.flatten_columns().into_iter()
+                .map(|(path, c)| (path, c.column_type.clone(), c.description.clone()))
                 .collect();
             println!("{}", serde_yaml::to_string(&columns_with_desc)?);
         }
         _ => {
             println!("{} - Column Descriptions", metadata.table.name.bold());
             println!("{}", "─".repeat(50));
-            for column in &metadata.columns {
-                println!("{} ({})", column.name.green(), column.column_type.blue());
+            for (path, column) in metadata.flatten_columns() {
+                print!("{} ({})", path.green(), column.column_type.blue());
+                if column.removed {
+                    print!(" {}", "[removed]".red());
+                }
+                println!();
                 if let Some(ref desc) = column.description {
                     println!("  {}", desc.italic());
                 } else {
@@ -217,28 +412,31 @@ fn display_column_descriptions(metadata: &TableMetadata, output_format: &str) ->
     Ok(())
 }
 
-fn create_table_metadata(project: &str, dataset: &str, table_name: &str, description: Option<String>) -> Result<()> {
+fn create_table_metadata(storage: &dyn Storage, project: &str, dataset: &str, table_name: &str, description: Option<String>) -> Result<()> {
     let metadata = TableMetadata {
         table: TableInfo {
             name: table_name.to_string(),
             project_id: project.to_string(),
             dataset_id: dataset.to_string(),
             description,
+            ..Default::default()
         },
         columns: Vec::new(),
     };
     
-    save_table_metadata(&metadata)?;
+    let _lock = CatalogLock::acquire()?;
+    save_table_metadata(storage, &metadata)?;
+    history::record(&format!("create: {}.{}.{}", project, dataset, table_name),
+                    &[(project.to_string(), dataset.to_string(), table_name.to_string())])?;
     println!("Created table metadata: {}.{}.{}", project, dataset, table_name);
     Ok(())
 }
 
-fn edit_column_description(project: &str, dataset: &str, table_name: &str, column_name: &str, new_description: Option<String>) -> Result<()> {
-    let mut metadata = load_table_metadata(project, dataset, table_name)?;
-    
-    let column = metadata.columns.iter_mut()
-        .find(|c| c.name == column_name)
-        .ok_or_else(|| anyhow::anyhow!("Column '{}' not found in table", column_name))?;
+fn edit_column_description(storage: &dyn Storage, project: &str, dataset: &str, table_name: &str, column_name: &str, new_description: Option<String>) -> Result<()> {
+    let missing_column = || anyhow::anyhow!("Column '{}' not found in table", column_name);
+    storage.load(project, dataset, table_name)?
+        .find_column(column_name)
+        .ok_or_else(missing_column)?;
     
     let description = if let Some(desc) = new_description {
         desc
@@ -250,20 +448,25 @@ fn edit_column_description(project: &str, dataset: &str, table_name: &str, colum
         input.trim().to_string()
     };
     
-    column.description = if description.is_empty() { None } else { Some(description) };
-    save_table_metadata(&metadata)?;
+    // Re-read under the lock so edits made while prompting aren't lost
+    let message = format!("edit: {}.{}.{}.{} description", project, dataset, table_name, column_name);
+    update_table_metadata(storage, project, dataset, table_name, &message, |metadata| {
+        let column = metadata.find_column_mut(column_name).ok_or_else(missing_column)?;
+        column.description = if description.is_empty() { None } else { Some(description) };
+        Ok(())
+    })?;
     
     println!("Updated description for column '{}' in {}.{}.{}", 
              column_name, project, dataset, table_name);
     Ok(())
 }
 
-fn export_table_metadata(project: &str, dataset: &str, table_name: &str, file_path: Option<&str>, format: &str) -> Result<()> {
-    let metadata = load_table_metadata(project, dataset, table_name)?;
+fn export_table_metadata(storage: &dyn Storage, project: &str, dataset: &str, table_name: &str, file_path: Option<&str>, format: &str) -> Result<()> {
+    let metadata = storage.load(project, dataset, table_name)?;
     
     let content = match format {
         "json" => serde_json::to_string_pretty(&metadata)?,
-        "yaml" => serde_yaml::to_string(&metadata)?,
+        "yaml" => migration::table_to_string(&metadata)?,
         _ => return Err(anyhow::anyhow!("Unsupported format: {}", format)),
     };
     
@@ -277,37 +480,603 @@ fn export_table_metadata(project: &str, dataset: &str, table_name: &str, file_pa
     Ok(())
 }
 
-fn import_table_metadata(file_path: &str, force: bool) -> Result<()> {
+fn import_table_metadata(storage: &dyn Storage, file_path: &str, force: bool) -> Result<()> {
     let content = std::fs::read_to_string(file_path)?;
     
     let metadata: TableMetadata = if file_path.ends_with(".json") {
         serde_json::from_str(&content)?
     } else {
-        serde_yaml::from_str(&content)?
+        migration::parse_table(&content)
+            .with_context(|| format!("Failed to parse {}", file_path))?
     };
     
-    let table_path = get_table_path(&metadata.table.project_id, &metadata.table.dataset_id, &metadata.table.name)?;
-    
-    if table_path.exists() && !force {
+    let _lock = CatalogLock::acquire()?;
+    if storage.exists(&metadata.table.project_id, &metadata.table.dataset_id, &metadata.table.name)? && !force {
         return Err(anyhow::anyhow!(
             "Table metadata already exists: {}.{}.{} (use --force to overwrite)",
             metadata.table.project_id, metadata.table.dataset_id, metadata.table.name
         ));
     }
     
-    save_table_metadata(&metadata)?;
+    save_table_metadata(storage, &metadata)?;
+    let imported = [(metadata.table.project_id.clone(), metadata.table.dataset_id.clone(), metadata.table.name.clone())];
+    history::record(&history::change_message("import", &imported), &imported)?;
     println!("Imported table metadata: {}.{}.{}", 
              metadata.table.project_id, metadata.table.dataset_id, metadata.table.name);
     
     Ok(())
 }
 
+fn import_bigquery_schema(storage: &dyn Storage, file_path: &str, project: &str, dataset: &str, table_name: &str, force: bool) -> Result<()> {
+    let content = std::fs::read_to_string(file_path)?;
+    let remote = TableMetadata {
+        table: TableInfo {
+            name: table_name.to_string(),
+            project_id: project.to_string(),
+            dataset_id: dataset.to_string(),
+            ..Default::default()
+        },
+        columns: bigquery::parse_schema(&content)?,
+    };
+
+    // A bare schema carries no table properties, so keep the local ones
+    let _lock = CatalogLock::acquire()?;
+    let merged = transaction(storage, || -> Result<bool> {
+        if storage.exists(project, dataset, table_name)? && !force {
+            let mut metadata = storage.load(project, dataset, table_name)?;
+            metadata.columns = bigquery::merge_columns(&metadata.columns, remote.columns);
+            save_table_metadata(storage, &metadata)?;
+            Ok(true)
+        } else {
+            save_table_metadata(storage, &remote)?;
+            Ok(false)
+        }
+    })?;
+    if merged {
+        println!("Merged BigQuery schema into: {}.{}.{}", project, dataset, table_name);
+    } else {
+        println!("Imported BigQuery schema: {}.{}.{}", project, dataset, table_name);
+    }
+    let imported = [(project.to_string(), dataset.to_string(), table_name.to_string())];
+    history::record(&history::change_message("import", &imported), &imported)?;
+    Ok(())
+}
+
+fn import_bigquery_resources(storage: &dyn Storage, path: &str, force: bool) -> Result<()> {
+    let path = std::path::Path::new(path);
+    let files = if path.is_dir() {
+        let mut files: Vec<_> = std::fs::read_dir(path)?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|p| p.extension().is_some_and(|ext| ext == "json"))
+            .collect();
+        files.sort();
+        files
+    } else {
+        vec![path.to_path_buf()]
+    };
+
+    let _lock = CatalogLock::acquire()?;
+    let mut imported = Vec::new();
+    for file in &files {
+        let content = std::fs::read_to_string(file)?;
+        let remote = bigquery::parse_table_resource(&content)
+            .with_context(|| format!("Failed to import: {}", file.display()))?;
+        let (project, dataset, table_name) = (
+            remote.table.project_id.clone(),
+            remote.table.dataset_id.clone(),
+            remote.table.name.clone(),
+        );
+
+        let merged = transaction(storage, || -> Result<bool> {
+            if storage.exists(&project, &dataset, &table_name)? && !force {
+                let local = storage.load(&project, &dataset, &table_name)?;
+                save_table_metadata(storage, &bigquery::merge_table_metadata(&local, remote))?;
+                Ok(true)
+            } else {
+                save_table_metadata(storage, &remote)?;
+                Ok(false)
+            }
+        })?;
+        if merged {
+            println!("Merged table resource into: {}.{}.{}", project, dataset, table_name);
+        } else {
+            println!("Imported table resource: {}.{}.{}", project, dataset, table_name);
+        }
+        imported.push((project, dataset, table_name));
+    }
+    history::record(&history::change_message("import", &imported), &imported)?;
+
+    println!("Imported {} table(s)", files.len());
+    Ok(())
+}
+
+fn export_ddl(storage: &dyn Storage, tables: &[(String, String, String)], file_path: Option<&str>) -> Result<()> {
+    let mut content = String::new();
+    for (project, dataset, table_name) in tables {
+        let metadata = storage.load(project, dataset, table_name)?;
+        for statement in ddl::generate_table_ddl(&metadata) {
+            content.push_str(&statement);
+            content.push('\n');
+        }
+    }
+
+    if let Some(path) = file_path {
+        std::fs::write(path, content)?;
+        println!("Exported DDL to: {}", path);
+    } else {
+        print!("{}", content);
+    }
+
+    Ok(())
+}
+
+struct TablePlan {
+    table_path: String,
+    local: TableMetadata,
+    remote: bigquery::TableResource,
+    changes: Vec<plan::DescriptionChange>,
+}
+
+async fn plan_tables(storage: &dyn Storage, tables: &[(String, String, String)]) -> Result<Vec<TablePlan>> {
+    let client = bigquery::BigQueryClient::from_config(&load_config()?);
+    let mut plans = Vec::new();
+
+    for (project, dataset, table_name) in tables {
+        let local = storage.load(project, dataset, table_name)?;
+        let remote = client.get_table(project, dataset, table_name).await
+            .with_context(|| format!("Failed to fetch {}.{}.{}", project, dataset, table_name))?;
+        let changes = plan::plan_descriptions(&local, &remote);
+        plans.push(TablePlan {
+            table_path: format!("{}.{}.{}", project, dataset, table_name),
+            local,
+            remote,
+            changes,
+        });
+    }
+
+    Ok(plans)
+}
+
+fn display_plan(plans: &[TablePlan], output_format: &str) -> Result<()> {
+    match output_format {
+        "json" => {
+            let json_plans: Vec<serde_json::Value> = plans.iter()
+                .filter(|p| !p.changes.is_empty())
+                .map(|p| serde_json::json!({
+                    "table_path": p.table_path,
+                    "changes": p.changes,
+                }))
+                .collect();
+            println!("{}", serde_json::to_string_pretty(&json_plans)?);
+        }
+        _ => {
+            let total: usize = plans.iter().map(|p| p.changes.len()).sum();
+            if total == 0 {
+                println!("No changes. BigQuery descriptions match local metadata.");
+                return Ok(());
+            }
+
+            for plan in plans.iter().filter(|p| !p.changes.is_empty()) {
+                println!("{} {}", "~".yellow(), plan.table_path.cyan());
+                for change in &plan.changes {
+                    let target = change.column.as_deref().unwrap_or("(table)");
+                    println!("    {}", target.green());
+                    println!("      {} {}", "-".red(), change.remote.as_deref().unwrap_or("(none)").red());
+                    println!("      {} {}", "+".green(), change.local.green());
+                }
+                println!();
+            }
+            println!("Plan: {} description change(s) in {} table(s)",
+                     total, plans.iter().filter(|p| !p.changes.is_empty()).count());
+        }
+    }
+    Ok(())
+}
+
+async fn apply_tables(storage: &dyn Storage, tables: &[(String, String, String)], yes: bool) -> Result<()> {
+    let plans = plan_tables(storage, tables).await?;
+    display_plan(&plans, "table")?;
+
+    let plans: Vec<&TablePlan> = plans.iter().filter(|p| !p.changes.is_empty()).collect();
+    if plans.is_empty() {
+        return Ok(());
+    }
+
+    if !yes {
+        print!("Apply these changes? [y/N]: ");
+        io::stdout().flush()?;
+        let mut input = String::new();
+        io::stdin().read_line(&mut input)?;
+        if !matches!(input.trim(), "y" | "Y" | "yes") {
+            println!("Apply cancelled.");
+            return Ok(());
+        }
+    }
+
+    let client = bigquery::BigQueryClient::from_config(&load_config()?);
+    for plan in plans {
+        let patch = plan::build_patch(&plan.local, &plan.remote, &plan.changes);
+        client.patch_table(&plan.local.table.project_id, &plan.local.table.dataset_id, &plan.local.table.name, &patch, plan.remote.etag.as_deref()).await
+            .with_context(|| format!("Failed to patch {}", plan.table_path))?;
+        println!("Applied {} change(s) to {}", plan.changes.len(), plan.table_path);
+    }
+
+    Ok(())
+}
+
+async fn pull_tables(storage: &dyn Storage, tables: &[(String, String, String)], output_format: &str) -> Result<()> {
+    let client = bigquery::BigQueryClient::from_config(&load_config()?);
+    let mut summaries = Vec::new();
+
+    for (project, dataset, table_name) in tables {
+        let remote = client.get_table(project, dataset, table_name).await
+            .with_context(|| format!("Failed to fetch {}.{}.{}", project, dataset, table_name))?
+            .into_metadata()?;
+
+        let _lock = CatalogLock::acquire()?;
+        let summary = transaction(storage, || -> Result<sync::SyncSummary> {
+            if storage.exists(project, dataset, table_name)? {
+                let mut local = storage.load(project, dataset, table_name)?;
+                let summary = sync::sync_table(&mut local, remote);
+                save_table_metadata(storage, &local)?;
+                Ok(summary)
+            } else {
+                let summary = sync::SyncSummary {
+                    added: remote.flatten_columns().into_iter().map(|(path, _)| path).collect(),
+                    ..Default::default()
+                };
+                save_table_metadata(storage, &remote)?;
+                Ok(summary)
+            }
+        })?;
+        summaries.push((format!("{}.{}.{}", project, dataset, table_name), summary));
+    }
+    // Tables are locked one at a time while fetching, so take the lock again to commit them
+    let lock = CatalogLock::acquire()?;
+    history::record(&history::change_message("pull", tables), tables)?;
+    drop(lock);
+
+    match output_format {
+        "json" => {
+            let json_summaries: Vec<serde_json::Value> = summaries.iter()
+                .map(|(table_path, summary)| serde_json::json!({
+                    "table_path": table_path,
+                    "added": summary.added,
+                    "updated": summary.updated,
+                    "removed": summary.removed,
+                    "descriptions_filled": summary.descriptions_filled,
+                }))
+                .collect();
+            println!("{}", serde_json::to_string_pretty(&json_summaries)?);
+        }
+        _ => {
+            for (table_path, summary) in &summaries {
+                if summary.is_empty() {
+                    println!("{} {}", table_path.cyan(), "up to date".dimmed());
+                    continue;
+                }
+
+                println!("{} {} added, {} updated, {} removed, {} description(s) filled",
+                         table_path.cyan(), summary.added.len(), summary.updated.len(),
+                         summary.removed.len(), summary.descriptions_filled.len());
+                for path in &summary.added {
+                    println!("  {} {}", "+".green(), path);
+                }
+                for change in &summary.updated {
+                    println!("  {} {}", "~".yellow(), change);
+                }
+                for path in &summary.removed {
+                    println!("  {} {} {}", "-".red(), path, "(flagged as removed)".dimmed());
+                }
+                for path in &summary.descriptions_filled {
+                    println!("  {} {} {}", "*".blue(), path, "(description from BigQuery)".dimmed());
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+async fn detect_drift(storage: &dyn Storage, table: Option<&str>, project: Option<&str>, dataset: Option<&str>, schemas: Option<&str>) -> Result<Vec<drift::DriftItem>> {
+    let requested = resolve_tables(storage, table, project, dataset)?;
+    // A single table spec isn't checked by resolve_tables; one missing locally is drift too
+    let mut local_tables = Vec::new();
+    for (p, d, t) in &requested {
+        if storage.exists(p, d, t)? {
+            local_tables.push((p.clone(), d.clone(), t.clone()));
+        }
+    }
+    let mut remote_tables: Vec<TableMetadata> = Vec::new();
+    let mut remote_names: Vec<(String, String, String)> = Vec::new();
+
+    if let Some(dir) = schemas {
+        for metadata in drift::load_schema_dumps(std::path::Path::new(dir))? {
+            let key = (metadata.table.project_id.clone(), metadata.table.dataset_id.clone(), metadata.table.name.clone());
+            let in_scope = match table {
+                Some(_) => requested.contains(&key),
+                None => project.is_none_or(|p| p == key.0) && dataset.is_none_or(|d| d == key.1),
+            };
+            if in_scope {
+                remote_names.push(key);
+                remote_tables.push(metadata);
+            }
+        }
+    } else {
+        let client = bigquery::BigQueryClient::from_config(&load_config()?);
+        for (p, d, t) in &requested {
+            if let Some(resource) = client.find_table(p, d, t).await? {
+                remote_tables.push(resource.into_metadata()?);
+                remote_names.push((p.clone(), d.clone(), t.clone()));
+            }
+        }
+
+        // Tables missing locally can only be discovered per dataset
+        if table.is_none() {
+            let mut datasets: Vec<(String, String)> = local_tables.iter()
+                .map(|(p, d, _)| (p.clone(), d.clone()))
+                .collect();
+            datasets.dedup();
+            for (p, d) in datasets {
+                for t in client.list_tables(&p, &d).await? {
+                    remote_names.push((p.clone(), d.clone(), t));
+                }
+            }
+        }
+    }
+
+    let mut items = Vec::new();
+    for (p, d, t) in &local_tables {
+        let table_path = format!("{}.{}.{}", p, d, t);
+        let local = storage.load(p, d, t)?;
+        match remote_tables.iter().find(|r| r.table.project_id == *p && r.table.dataset_id == *d && r.table.name == *t) {
+            Some(remote) => items.extend(drift::compare_tables(&local, remote)),
+            None => items.push(drift::DriftItem {
+                table_path,
+                kind: drift::DriftKind::ExtraTable,
+                column: None,
+                local: None,
+                remote: None,
+            }),
+        }
+    }
+
+    if local_tables.is_empty() && remote_names.is_empty() {
+        if let Some(spec) = table {
+            return Err(anyhow::anyhow!("Table not found locally or remotely: {}", spec));
+        }
+    }
+
+    for name in remote_names {
+        if !local_tables.contains(&name) {
+            items.push(drift::DriftItem {
+                table_path: format!("{}.{}.{}", name.0, name.1, name.2),
+                kind: drift::DriftKind::MissingTable,
+                column: None,
+                local: None,
+                remote: None,
+            });
+        }
+    }
+
+    Ok(items)
+}
+
+fn display_drift(items: &[drift::DriftItem], output_format: &str) -> Result<()> {
+    match output_format {
+        "json" => {
+            println!("{}", serde_json::to_string_pretty(items)?);
+        }
+        _ => {
+            if items.is_empty() {
+                println!("No drift detected.");
+                return Ok(());
+            }
+
+            for item in items {
+                let kind = format!("[{}]", item.kind);
+                let kind_colored = match item.kind {
+                    drift::DriftKind::MissingTable | drift::DriftKind::AddedColumn => kind.green(),
+                    drift::DriftKind::ExtraTable | drift::DriftKind::RemovedColumn => kind.red(),
+                    _ => kind.yellow(),
+                };
+
+                print!("{} {}", kind_colored, item.table_path.cyan());
+                if let Some(ref column) = item.column {
+                    print!(" ({})", column.dimmed());
+                }
+                println!();
+                if item.local.is_some() || item.remote.is_some() {
+                    println!("  local:  {}", item.local.as_deref().unwrap_or("(none)"));
+                    println!("  remote: {}", item.remote.as_deref().unwrap_or("(none)"));
+                }
+            }
+            println!("\n{} drift item(s) found", items.len());
+        }
+    }
+    Ok(())
+}
+
+fn format_percent(value: f64) -> ColoredString {
+    let text = format!("{:5.1}%", value);
+    if value >= 80.0 {
+        text.green()
+    } else if value >= 50.0 {
+        text.yellow()
+    } else {
+        text.red()
+    }
+}
+
+fn display_coverage(report: &coverage::CoverageReport, output_format: &str) -> Result<()> {
+    match output_format {
+        "json" => {
+            println!("{}", serde_json::to_string_pretty(report)?);
+        }
+        _ => {
+            if report.coverage.tables == 0 {
+                println!("No tables found.");
+                return Ok(());
+            }
+
+            println!("{:<50} {:>8} {:>8} {:>12}", "Scope".bold(), "Tables".bold(), "Columns".bold(), "Documented".bold());
+            println!("{}", "─".repeat(82));
+            for project in &report.projects {
+                println!("{:<50} {:>8} {:>8} {:>12}", project.project.cyan(),
+                         format_percent(project.table_percent), format_percent(project.column_percent),
+                         format!("{}/{}", project.coverage.columns_documented, project.coverage.columns));
+                for dataset in &project.datasets {
+                    println!("  {:<48} {:>8} {:>8} {:>12}", dataset.dataset.yellow(),
+                             format_percent(dataset.table_percent), format_percent(dataset.column_percent),
+                             format!("{}/{}", dataset.coverage.columns_documented, dataset.coverage.columns));
+                    for table in &dataset.table_coverage {
+                        println!("    {:<46} {:>8} {:>8} {:>12}", table.table.green(),
+                                 format_percent(table.table_percent), format_percent(table.column_percent),
+                                 format!("{}/{}", table.coverage.columns_documented, table.coverage.columns));
+                    }
+                }
+            }
+            println!("{}", "─".repeat(82));
+            println!("{:<50} {:>8} {:>8} {:>12}", "Total".bold(),
+                     format_percent(report.table_percent), format_percent(report.column_percent),
+                     format!("{}/{}", report.coverage.columns_documented, report.coverage.columns));
+        }
+    }
+    Ok(())
+}
+
+fn display_lint(diagnostics: &[lint::Diagnostic], output_format: &str) -> Result<()> {
+    match output_format {
+        "json" => {
+            println!("{}", serde_json::to_string_pretty(diagnostics)?);
+        }
+        "sarif" => {
+            println!("{}", serde_json::to_string_pretty(&lint::to_sarif(diagnostics))?);
+        }
+        _ => {
+            if diagnostics.is_empty() {
+                println!("No problems found.");
+                return Ok(());
+            }
+
+            for diagnostic in diagnostics {
+                let severity = match diagnostic.severity {
+                    Severity::Error => format!("[{}]", "error".red()),
+                    Severity::Warning => format!("[{}]", "warning".yellow()),
+                    Severity::Info => format!("[{}]", "info".blue()),
+                };
+                print!("{} {}", severity, diagnostic.table_path.cyan());
+                if let Some(ref column) = diagnostic.column {
+                    print!(" ({})", column.dimmed());
+                }
+                println!();
+                println!("  {} {}", diagnostic.message, format!("[{}]", diagnostic.rule).dimmed());
+            }
+
+            let count = |severity| diagnostics.iter().filter(|d| d.severity == severity).count();
+            println!("\n{} error(s), {} warning(s), {} info", count(Severity::Error), count(Severity::Warning), count(Severity::Info));
+        }
+    }
+    Ok(())
+}
+
+fn display_history(entries: &[history::HistoryEntry], output_format: &str) -> Result<()> {
+    match output_format {
+        "json" => {
+            println!("{}", serde_json::to_string_pretty(entries)?);
+        }
+        _ => {
+            if entries.is_empty() {
+                println!("No history recorded.");
+                return Ok(());
+            }
+
+            for entry in entries {
+                println!("{} {} {} {}", entry.commit.yellow(), entry.date.dimmed(),
+                         format!("<{}>", entry.author).cyan(), entry.message);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn display_blame(entries: &[history::BlameEntry], output_format: &str) -> Result<()> {
+    match output_format {
+        "json" => {
+            println!("{}", serde_json::to_string_pretty(entries)?);
+        }
+        _ => {
+            println!("{:<30} {:<8} {:<16} {:<16} {}", "Column".bold(), "Commit".bold(), "Date (UTC)".bold(),
+                     "Author".bold(), "Description".bold());
+            println!("{}", "─".repeat(100));
+
+            for entry in entries {
+                let target = entry.column.clone().unwrap_or_else(|| "[TABLE]".to_string());
+                let description = entry.description.as_deref().unwrap_or("-");
+                match entry.change {
+                    Some(ref change) => println!("{:<30} {:<8} {:<16} {:<16} {}", target.cyan(), change.commit.yellow(),
+                                                 change.date, change.author, description),
+                    None if entry.description.is_none() => println!("{:<30} {:<8} {:<16} {:<16} {}", target.cyan(),
+                                                                    "-".dimmed(), "-".dimmed(), "", description),
+                    None => println!("{:<30} {:<8} {:<16} {:<16} {}", target.cyan(), "-".dimmed(),
+                                     "uncommitted".dimmed(), "", description),
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn report_journal_step(action: journal::JournalAction, entries: &[journal::JournalEntry]) {
+    let verb = if action == journal::JournalAction::Undo { "Reverted" } else { "Reapplied" };
+    for entry in entries {
+        println!("{} {} {}", verb, entry.table_path().cyan(), format!("({})", journal::summarize(entry)).dimmed());
+    }
+}
+
+fn display_log(entries: &[journal::LogEntry], output_format: &str) -> Result<()> {
+    match output_format {
+        "json" => {
+            println!("{}", serde_json::to_string_pretty(entries)?);
+        }
+        _ => {
+            if entries.is_empty() {
+                println!("No changes recorded.");
+                return Ok(());
+            }
+
+            for entry in entries {
+                print!("{} {} {} {} {}", format!("#{:<4}", entry.id).yellow(), entry.date.dimmed(),
+                       format!("{:<4}", entry.action).bold(), entry.table_path.cyan(), entry.summary);
+                if entry.undone {
+                    print!(" {}", "(undone)".dimmed());
+                }
+                println!();
+            }
+        }
+    }
+    Ok(())
+}
+
+fn parse_storage_backend(value: &str) -> Result<StorageBackend> {
+    match value {
+        "yaml" => Ok(StorageBackend::Yaml),
+        "sqlite" => Ok(StorageBackend::Sqlite),
+        _ => Err(anyhow::anyhow!("Invalid storage backend: {} (expected yaml or sqlite)", value)),
+    }
+}
+
 fn set_config_value(key: &str, value: &str) -> Result<()> {
+    // Held so concurrent `config set` calls (and `migrate`) don't lose each other's changes
+    let _lock = CatalogLock::acquire()?;
     let mut config = load_config()?;
     
     match key {
         "default_project" => config.default_project = Some(value.to_string()),
         "default_dataset" => config.default_dataset = Some(value.to_string()),
+        "api_endpoint" => config.api_endpoint = Some(value.to_string()),
+        "storage" => config.storage.backend = parse_storage_backend(value)?,
+        "storage_path" => config.storage.path = Some(value.to_string()),
         "output_format" => {
             config.output_format = match value {
                 "table" => OutputFormat::Table,
@@ -330,6 +1099,9 @@ fn get_config_value(key: &str) -> Result<()> {
     let value = match key {
         "default_project" => config.default_project.unwrap_or_else(|| "None".to_string()),
         "default_dataset" => config.default_dataset.unwrap_or_else(|| "None".to_string()),
+        "api_endpoint" => config.api_endpoint.unwrap_or_else(|| bigquery::DEFAULT_API_ENDPOINT.to_string()),
+        "storage" => config.storage.backend.to_string(),
+        "storage_path" => config.storage.path.unwrap_or_else(|| "catalog.db".to_string()),
         "output_format" => match config.output_format {
             OutputFormat::Table => "table".to_string(),
             OutputFormat::Json => "json".to_string(),
@@ -354,6 +1126,11 @@ fn list_config() -> Result<()> {
         OutputFormat::Json => "json", 
         OutputFormat::Yaml => "yaml",
     });
+    println!("api_endpoint: {}", config.api_endpoint.as_deref().unwrap_or(bigquery::DEFAULT_API_ENDPOINT));
+    println!("storage: {}", config.storage.backend);
+    if config.storage.backend == StorageBackend::Sqlite {
+        println!("storage_path: {}", config.storage.path.as_deref().unwrap_or("catalog.db"));
+    }
     
     Ok(())
 }
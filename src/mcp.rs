@@ -0,0 +1,292 @@
+use anyhow::Result;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::io::{BufRead, Write};
+
+use crate::cli::parse_table_spec;
+use crate::config::get_data_dir;
+use crate::models::{MatchType, TableMetadata};
+use crate::search::{search_tables, SearchOptions};
+use crate::storage::open_storage;
+
+const SUPPORTED_PROTOCOL_VERSIONS: &[&str] = &["2025-06-18", "2025-03-26", "2024-11-05"];
+
+// JSON-RPC error codes
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+fn tool_definitions() -> Value {
+    json!([
+        {
+            "name": "list_tables",
+            "description": "List BigQuery tables in the metadata catalog as project.dataset.table",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "project": { "type": "string", "description": "Project ID to filter" },
+                    "dataset": { "type": "string", "description": "Dataset ID to filter (requires project)" }
+                }
+            }
+        },
+        {
+            "name": "describe_table",
+            "description": "Get the description and all columns (name, type, mode, description) of a table",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "table": { "type": "string", "description": "Table as project.dataset.table" }
+                },
+                "required": ["table"]
+            }
+        },
+        {
+            "name": "search_columns",
+            "description": "Find columns whose name or description matches a pattern, with their tables and types",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "pattern": { "type": "string", "description": "Case-insensitive substring to search for" },
+                    "regex": { "type": "boolean", "description": "Treat the pattern as a regular expression" },
+                    "project": { "type": "string", "description": "Project ID to filter" },
+                    "dataset": { "type": "string", "description": "Dataset ID to filter (requires project)" },
+                    "limit": { "type": "integer", "description": "Maximum number of matches, best first" }
+                },
+                "required": ["pattern"]
+            }
+        },
+        {
+            "name": "search_tables",
+            "description": "Find tables whose name or description matches a pattern",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "pattern": { "type": "string", "description": "Case-insensitive substring to search for" },
+                    "regex": { "type": "boolean", "description": "Treat the pattern as a regular expression" },
+                    "project": { "type": "string", "description": "Project ID to filter" },
+                    "dataset": { "type": "string", "description": "Dataset ID to filter (requires project)" },
+                    "limit": { "type": "integer", "description": "Maximum number of matches, best first" }
+                },
+                "required": ["pattern"]
+            }
+        }
+    ])
+}
+
+fn response(id: Value, result: Value) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "result": result })
+}
+
+fn error_response(id: Value, code: i64, message: &str) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message } })
+}
+
+fn string_arg(args: &Value, name: &str) -> Option<String> {
+    args.get(name).and_then(Value::as_str).map(str::to_string)
+}
+
+fn search_options(args: &Value) -> Result<SearchOptions> {
+    let pattern = string_arg(args, "pattern")
+        .ok_or_else(|| anyhow::anyhow!("Missing required argument: pattern"))?;
+    Ok(SearchOptions {
+        pattern,
+        regex: args.get("regex").and_then(Value::as_bool).unwrap_or(false),
+        project_filter: string_arg(args, "project"),
+        dataset_filter: string_arg(args, "dataset"),
+        limit: args.get("limit").and_then(Value::as_u64).map(|n| n as usize),
+        ..Default::default()
+    })
+}
+
+fn call_tool(name: &str, args: &Value) -> Result<Value> {
+    match name {
+        "list_tables" => {
+            let tables = open_storage()?.list(string_arg(args, "project").as_deref(), string_arg(args, "dataset").as_deref())?;
+            let names: Vec<String> = tables.into_iter()
+                .map(|(p, d, t)| format!("{}.{}.{}", p, d, t))
+                .collect();
+            Ok(json!(names))
+        }
+        "describe_table" => {
+            let spec = string_arg(args, "table")
+                .ok_or_else(|| anyhow::anyhow!("Missing required argument: table"))?;
+            let (project, dataset, table) = parse_table_spec(&spec).map_err(|e| anyhow::anyhow!(e))?;
+            let metadata = open_storage()?.load(&project, &dataset, &table)?;
+            let columns: Vec<Value> = metadata.flatten_columns().into_iter()
+                .filter(|(_, c)| !c.removed)
+                .map(|(path, c)| json!({
+                    "name": path,
+                    "type": c.column_type,
+                    "mode": c.mode.as_deref().unwrap_or("NULLABLE"),
+                    "description": c.description,
+                }))
+                .collect();
+            Ok(json!({
+                "table": spec,
+                "description": metadata.table.description,
+                "table_type": metadata.table.table_type,
+                "columns": columns,
+            }))
+        }
+        "search_columns" => {
+            let options = SearchOptions {
+                search_column_name: true,
+                search_column_desc: true,
+                ..search_options(args)?
+            };
+
+            let storage = open_storage()?;
+            let mut tables: HashMap<String, TableMetadata> = HashMap::new();
+            let mut columns: Vec<Value> = Vec::new();
+            for result in search_tables(&options)?.results {
+                let Some(path) = result.column.as_deref() else { continue };
+                if !tables.contains_key(&result.table_path) {
+                    let (p, d, t) = parse_table_spec(&result.table_path).map_err(|e| anyhow::anyhow!(e))?;
+                    tables.insert(result.table_path.clone(), storage.load(&p, &d, &t)?);
+                }
+                let Some(column) = tables[&result.table_path].find_column(path) else { continue };
+                // A column can match on both name and description
+                if columns.iter().any(|c| c["table"] == result.table_path.as_str() && c["column"] == path) {
+                    continue;
+                }
+                columns.push(json!({
+                    "table": result.table_path,
+                    "column": path,
+                    "type": column.column_type,
+                    "description": column.description,
+                }));
+            }
+            Ok(json!(columns))
+        }
+        "search_tables" => {
+            let options = SearchOptions {
+                search_table_desc: true,
+                ..search_options(args)?
+            };
+            let results: Vec<Value> = search_tables(&options)?.results.into_iter()
+                .map(|r| json!({
+                    "table": r.table_path,
+                    "match": match r.match_type {
+                        MatchType::TableDescription => "description",
+                        _ => "name",
+                    },
+                    "matched_content": r.matched_content,
+                    "score": r.score,
+                }))
+                .collect();
+            Ok(json!(results))
+        }
+        _ => Err(anyhow::anyhow!("Unknown tool: {}", name)),
+    }
+}
+
+/// Handle one JSON-RPC message, returning the response (`None` for notifications)
+pub fn handle_message(message: &Value) -> Option<Value> {
+    let id = message.get("id").cloned();
+    let Some(method) = message.get("method").and_then(Value::as_str) else {
+        return Some(error_response(id.unwrap_or(Value::Null), INVALID_REQUEST, "Invalid request"));
+    };
+    // Notifications (no id) never get a response
+    let id = id?;
+    let params = message.get("params").cloned().unwrap_or(Value::Null);
+
+    let result = match method {
+        "initialize" => {
+            let requested = params.get("protocolVersion").and_then(Value::as_str);
+            let version = requested
+                .filter(|v| SUPPORTED_PROTOCOL_VERSIONS.contains(v))
+                .unwrap_or(SUPPORTED_PROTOCOL_VERSIONS[0]);
+            json!({
+                "protocolVersion": version,
+                "capabilities": { "tools": {} },
+                "serverInfo": { "name": "bq-meta", "version": env!("CARGO_PKG_VERSION") },
+            })
+        }
+        "ping" => json!({}),
+        "tools/list" => json!({ "tools": tool_definitions() }),
+        "tools/call" => {
+            let Some(name) = params.get("name").and_then(Value::as_str) else {
+                return Some(error_response(id, INVALID_PARAMS, "Missing tool name"));
+            };
+            let args = params.get("arguments").cloned().unwrap_or(json!({}));
+            // Tool failures are reported to the model as results, not protocol errors
+            match call_tool(name, &args) {
+                Ok(value) => json!({
+                    "content": [{ "type": "text", "text": serde_json::to_string_pretty(&value).unwrap_or_default() }],
+                    "isError": false,
+                }),
+                Err(err) => json!({
+                    "content": [{ "type": "text", "text": format!("{:#}", err) }],
+                    "isError": true,
+                }),
+            }
+        }
+        _ => return Some(error_response(id, METHOD_NOT_FOUND, &format!("Method not found: {}", method))),
+    };
+
+    Some(response(id, result))
+}
+
+/// Serve MCP over stdin/stdout with newline-delimited JSON-RPC messages
+pub fn run_stdio() -> Result<()> {
+    let stdin = std::io::stdin();
+    let mut stdout = std::io::stdout();
+
+    // Make sure the data directory is resolvable before accepting requests
+    get_data_dir()?;
+
+    for line in stdin.lock().lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let reply = match serde_json::from_str::<Value>(&line) {
+            Ok(Value::Array(batch)) => {
+                let replies: Vec<Value> = batch.iter().filter_map(handle_message).collect();
+                (!replies.is_empty()).then_some(Value::Array(replies))
+            }
+            Ok(message) => handle_message(&message),
+            Err(err) => Some(error_response(Value::Null, PARSE_ERROR, &format!("Parse error: {}", err))),
+        };
+
+        if let Some(reply) = reply {
+            writeln!(stdout, "{}", reply)?;
+            stdout.flush()?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_handle_message() {
+        let init = handle_message(&json!({
+            "jsonrpc": "2.0", "id": 1, "method": "initialize",
+            "params": { "protocolVersion": "2024-11-05", "capabilities": {} }
+        })).unwrap();
+        assert_eq!(init["result"]["protocolVersion"], "2024-11-05");
+        assert_eq!(init["result"]["serverInfo"]["name"], "bq-meta");
+
+        assert!(handle_message(&json!({ "jsonrpc": "2.0", "method": "notifications/initialized" })).is_none());
+
+        let tools = handle_message(&json!({ "jsonrpc": "2.0", "id": 2, "method": "tools/list" })).unwrap();
+        let names: Vec<&str> = tools["result"]["tools"].as_array().unwrap().iter()
+            .map(|t| t["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(names, vec!["list_tables", "describe_table", "search_columns", "search_tables"]);
+
+        let unknown = handle_message(&json!({ "jsonrpc": "2.0", "id": 3, "method": "resources/list" })).unwrap();
+        assert_eq!(unknown["error"]["code"], METHOD_NOT_FOUND);
+
+        let bad_tool = handle_message(&json!({
+            "jsonrpc": "2.0", "id": 4, "method": "tools/call", "params": { "name": "drop_table" }
+        })).unwrap();
+        assert_eq!(bad_tool["result"]["isError"], true);
+    }
+}
@@ -0,0 +1,226 @@
+use anyhow::{Context, Result};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_yaml::{Mapping, Value};
+
+use crate::config::{get_config_path, load_config, save_config};
+use crate::history;
+use crate::models::{Config, TableMetadata};
+use crate::storage::{open_storage, CatalogLock};
+
+/// Current format of table files and `config.yaml`, written as their `format_version` key.
+/// Files without the key predate versioning and are version 0.
+pub const FORMAT_VERSION: u32 = 1;
+
+const VERSION_KEY: &str = "format_version";
+
+/// Upgrades a document in place from one version to the next
+type Migration = fn(&mut Mapping) -> Result<()>;
+
+/// Table file migrations; entry `i` upgrades version `i` to `i + 1`
+const TABLE_MIGRATIONS: [Migration; FORMAT_VERSION as usize] = [
+    unversioned,
+];
+
+/// `config.yaml` migrations; entry `i` upgrades version `i` to `i + 1`
+const CONFIG_MIGRATIONS: [Migration; FORMAT_VERSION as usize] = [
+    unversioned,
+];
+
+/// 0 -> 1: the layout is unchanged, files only gain `format_version`
+fn unversioned(_: &mut Mapping) -> Result<()> {
+    Ok(())
+}
+
+/// A file written by a newer bq-meta, which this version refuses to read or overwrite
+#[derive(Debug)]
+pub struct NewerFormatError {
+    pub found: u32,
+}
+
+impl std::fmt::Display for NewerFormatError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "format version {} is newer than this bq-meta supports ({}); upgrade bq-meta to use this file",
+               self.found, FORMAT_VERSION)
+    }
+}
+
+impl std::error::Error for NewerFormatError {}
+
+/// Whether an error (or any error it wraps) is a `NewerFormatError`
+pub fn is_newer_format(err: &anyhow::Error) -> bool {
+    err.chain().any(|cause| cause.is::<NewerFormatError>())
+}
+
+/// Version of a parsed document, failing for versions newer than `FORMAT_VERSION`
+fn version_of(document: &Mapping) -> Result<u32> {
+    let version = match document.get(VERSION_KEY) {
+        None => 0,
+        Some(value) => value.as_u64()
+            .and_then(|v| u32::try_from(v).ok())
+            .with_context(|| format!("{} must be a non-negative integer", VERSION_KEY))?,
+    };
+    if version > FORMAT_VERSION {
+        return Err(NewerFormatError { found: version }.into());
+    }
+    Ok(version)
+}
+
+fn parse_mapping(text: &str) -> Result<Mapping> {
+    match serde_yaml::from_str(text)? {
+        Value::Null => Ok(Mapping::new()),
+        Value::Mapping(document) => Ok(document),
+        _ => Err(anyhow::anyhow!("expected a mapping at the top level")),
+    }
+}
+
+/// Format version of a document, failing for versions newer than `FORMAT_VERSION`
+pub fn document_version(text: &str) -> Result<u32> {
+    version_of(&parse_mapping(text)?)
+}
+
+fn parse_versioned<T: DeserializeOwned>(text: &str, migrations: &[Migration]) -> Result<T> {
+    let document = parse_mapping(text)?;
+    let version = version_of(&document)?;
+    if version == FORMAT_VERSION {
+        // Parse the text directly so errors keep their line numbers
+        return Ok(serde_yaml::from_str(text)?);
+    }
+    upgrade(document, version, migrations)
+}
+
+fn upgrade<T: DeserializeOwned>(mut document: Mapping, version: u32, migrations: &[Migration]) -> Result<T> {
+    for migration in &migrations[version as usize..] {
+        migration(&mut document)?;
+    }
+    document.remove(VERSION_KEY);
+    Ok(serde_yaml::from_value(Value::Mapping(document))?)
+}
+
+fn to_versioned_string<T: Serialize>(value: &T) -> Result<String> {
+    let Value::Mapping(fields) = serde_yaml::to_value(value)? else {
+        return Err(anyhow::anyhow!("expected a mapping at the top level"));
+    };
+    let mut document = Mapping::new();
+    document.insert(VERSION_KEY.into(), FORMAT_VERSION.into());
+    document.extend(fields);
+    Ok(serde_yaml::to_string(&document)?)
+}
+
+/// Parse a table document, upgrading older formats
+pub fn parse_table(text: &str) -> Result<TableMetadata> {
+    parse_versioned(text, &TABLE_MIGRATIONS)
+}
+
+/// Upgrade a table stored without its own `format_version`, such as an undo journal snapshot
+/// recorded at `version`
+pub fn upgrade_table(value: Value, version: u32) -> Result<TableMetadata> {
+    if version > FORMAT_VERSION {
+        return Err(NewerFormatError { found: version }.into());
+    }
+    match value {
+        Value::Mapping(document) => upgrade(document, version, &TABLE_MIGRATIONS),
+        _ => Err(anyhow::anyhow!("expected a mapping at the top level")),
+    }
+}
+
+/// Serialize a table in the current format
+pub fn table_to_string(metadata: &TableMetadata) -> Result<String> {
+    to_versioned_string(metadata)
+}
+
+/// Parse `config.yaml`, upgrading older formats
+pub fn parse_config(text: &str) -> Result<Config> {
+    parse_versioned(text, &CONFIG_MIGRATIONS)
+}
+
+/// Serialize the configuration in the current format
+pub fn config_to_string(config: &Config) -> Result<String> {
+    to_versioned_string(config)
+}
+
+/// What `bq-meta migrate` rewrote (or would rewrite)
+#[derive(Debug, Default)]
+pub struct MigrationReport {
+    pub config: bool,
+    pub tables: Vec<(String, String, String)>,
+    /// Tables that couldn't be migrated (unparseable or from a newer bq-meta), as
+    /// `(location, reason)`
+    pub failed: Vec<(String, String)>,
+}
+
+/// Rewrite `config.yaml` and every table stored in an older format. Reading already upgrades
+/// them in memory; this persists the upgrade and commits it to git history when enabled.
+/// With `dry_run` nothing is written. Tables that can't be migrated are reported in `failed`
+/// rather than stopping the others.
+pub fn migrate(dry_run: bool) -> Result<MigrationReport> {
+    let _lock = CatalogLock::acquire()?;
+    let mut report = MigrationReport::default();
+
+    let config_path = get_config_path()?;
+    if config_path.exists() {
+        let content = std::fs::read_to_string(&config_path)
+            .with_context(|| format!("Failed to read config file: {}", config_path.display()))?;
+        let version = document_version(&content)
+            .with_context(|| format!("Failed to parse config file: {}", config_path.display()))?;
+        if version < FORMAT_VERSION {
+            if !dry_run {
+                save_config(&load_config()?)?;
+            }
+            report.config = true;
+        }
+    }
+
+    let storage = open_storage()?;
+    for (project_id, dataset_id, table_name) in storage.list(None, None)? {
+        let migrated = storage.format_version(&project_id, &dataset_id, &table_name)
+            .and_then(|version| {
+                if version == FORMAT_VERSION {
+                    return Ok(false);
+                }
+                // Load even on a dry run, so tables that would fail to upgrade are reported
+                let metadata = storage.load(&project_id, &dataset_id, &table_name)?;
+                if !dry_run {
+                    storage.save(&metadata)?;
+                }
+                Ok(true)
+            });
+        match migrated {
+            Ok(true) => report.tables.push((project_id, dataset_id, table_name)),
+            Ok(false) => {}
+            Err(err) => report.failed.push((
+                storage.location(&project_id, &dataset_id, &table_name)?,
+                format!("{:#}", err),
+            )),
+        }
+    }
+    if !dry_run && !report.tables.is_empty() {
+        history::record(&history::change_message("migrate", &report.tables), &report.tables)?;
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_versioned_documents() {
+        // Unversioned files are upgraded on read and written with the current version
+        let metadata = parse_table("table:\n  name: t\n  project_id: p\n  dataset_id: d\n  description: null\ncolumns: []\n").unwrap();
+        assert_eq!(metadata.table.name, "t");
+        let written = table_to_string(&metadata).unwrap();
+        assert!(written.starts_with(&format!("format_version: {}\n", FORMAT_VERSION)));
+        assert_eq!(parse_table(&written).unwrap(), metadata);
+        assert_eq!(document_version(&written).unwrap(), FORMAT_VERSION);
+
+        // Newer files are rejected
+        let newer = written.replacen(&format!("format_version: {}", FORMAT_VERSION),
+                                     &format!("format_version: {}", FORMAT_VERSION + 1), 1);
+        let err = parse_table(&newer).unwrap_err();
+        assert!(is_newer_format(&err));
+
+        let config = parse_config("default_project: null\ndefault_dataset: null\noutput_format: Table\n").unwrap();
+        assert!(config_to_string(&config).unwrap().starts_with("format_version:"));
+    }
+}
@@ -1,26 +1,114 @@
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TableMetadata {
     pub table: TableInfo,
     pub columns: Vec<ColumnInfo>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct TableInfo {
     pub name: String,
     pub project_id: String,
     pub dataset_id: String,
     pub description: Option<String>,
+    /// BigQuery table type (`TABLE`, `VIEW`, `MATERIALIZED_VIEW`, `EXTERNAL`, ...)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub table_type: Option<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub labels: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub partitioning: Option<Partitioning>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub clustering: Vec<String>,
+    /// Table expiration in milliseconds since the epoch
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expiration_time: Option<i64>,
+    /// SQL of a view or materialized view
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub view_query: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Partitioning {
+    /// `DAY`, `HOUR`, `MONTH`, `YEAR` for time partitioning or `RANGE` for integer range partitioning
+    #[serde(rename = "type")]
+    pub partition_type: String,
+    /// Partitioning column; `None` means ingestion-time partitioning
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub field: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expiration_ms: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub range: Option<PartitionRange>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PartitionRange {
+    pub start: i64,
+    pub end: i64,
+    pub interval: i64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct ColumnInfo {
     pub name: String,
     #[serde(rename = "type")]
     pub column_type: String,
     pub description: Option<String>,
     pub mode: Option<String>,
+    /// Child columns of a `RECORD`/`STRUCT` column
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fields: Vec<ColumnInfo>,
+    /// Data Catalog policy tag resource names attached to the column
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub policy_tags: Vec<String>,
+    /// Set by `pull` when the column no longer exists in BigQuery
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub removed: bool,
+}
+
+impl TableMetadata {
+    /// Find a column by its dotted path (e.g. `event_params.value.string_value`)
+    pub fn find_column(&self, path: &str) -> Option<&ColumnInfo> {
+        let mut parts = path.split('.');
+        let first = parts.next()?;
+        let mut column = self.columns.iter().find(|c| c.name == first)?;
+        for part in parts {
+            column = column.fields.iter().find(|c| c.name == part)?;
+        }
+        Some(column)
+    }
+
+    /// Find a column by its dotted path for modification
+    pub fn find_column_mut(&mut self, path: &str) -> Option<&mut ColumnInfo> {
+        let mut parts = path.split('.');
+        let first = parts.next()?;
+        let mut column = self.columns.iter_mut().find(|c| c.name == first)?;
+        for part in parts {
+            column = column.fields.iter_mut().find(|c| c.name == part)?;
+        }
+        Some(column)
+    }
+
+    /// All columns including nested fields, paired with their dotted paths, in depth-first order
+    pub fn flatten_columns(&self) -> Vec<(String, &ColumnInfo)> {
+        fn collect<'a>(prefix: Option<&str>, columns: &'a [ColumnInfo], out: &mut Vec<(String, &'a ColumnInfo)>) {
+            for column in columns {
+                let path = match prefix {
+                    Some(p) => format!("{}.{}", p, column.name),
+                    None => column.name.clone(),
+                };
+                out.push((path.clone(), column));
+                collect(Some(&path), &column.fields, out);
+            }
+        }
+
+        let mut out = Vec::new();
+        collect(None, &self.columns, &mut out);
+        out
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -28,6 +116,109 @@ pub struct Config {
     pub default_project: Option<String>,
     pub default_dataset: Option<String>,
     pub output_format: OutputFormat,
+    /// BigQuery REST API base URL; defaults to the public endpoint
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_endpoint: Option<String>,
+    #[serde(default, skip_serializing_if = "LintConfig::is_default")]
+    pub lint: LintConfig,
+    #[serde(default, skip_serializing_if = "StorageConfig::is_default")]
+    pub storage: StorageConfig,
+}
+
+/// Where table metadata is kept
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct StorageConfig {
+    #[serde(default)]
+    pub backend: StorageBackend,
+    /// SQLite database file; defaults to `catalog.db` in the data path
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+}
+
+impl StorageConfig {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StorageBackend {
+    /// One YAML file per table under `data/{project}/{dataset}/`
+    #[default]
+    Yaml,
+    /// A single SQLite database file
+    Sqlite,
+}
+
+impl std::fmt::Display for StorageBackend {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StorageBackend::Yaml => write!(f, "yaml"),
+            StorageBackend::Sqlite => write!(f, "sqlite"),
+        }
+    }
+}
+
+/// Settings for the `lint` command
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LintConfig {
+    /// Per-rule overrides keyed by rule id (e.g. `missing-column-description`)
+    #[serde(default)]
+    pub rules: BTreeMap<String, LintRuleConfig>,
+    /// Minimum length for the `short-description` rule
+    #[serde(default = "default_min_description_length")]
+    pub min_description_length: usize,
+}
+
+fn default_min_description_length() -> usize {
+    10
+}
+
+impl Default for LintConfig {
+    fn default() -> Self {
+        Self {
+            rules: BTreeMap::new(),
+            min_description_length: default_min_description_length(),
+        }
+    }
+}
+
+impl LintConfig {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LintRuleConfig {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Overrides the rule's default severity
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub severity: Option<Severity>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+impl std::fmt::Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Severity::Info => write!(f, "info"),
+            Severity::Warning => write!(f, "warning"),
+            Severity::Error => write!(f, "error"),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -43,16 +234,27 @@ impl Default for Config {
             default_project: None,
             default_dataset: None,
             output_format: OutputFormat::Table,
+            api_endpoint: None,
+            lint: LintConfig::default(),
+            storage: StorageConfig::default(),
         }
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct SearchResult {
     pub table_path: String,
     pub match_type: MatchType,
     pub matched_content: String,
     pub context: Option<String>,
+    /// Dotted path of the matched column, set for column name and description matches
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub column: Option<String>,
+    /// Relevance score; results are ordered from highest to lowest
+    pub score: f64,
+    /// Similarity to the pattern (0.0-1.0), set for fuzzy searches
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub similarity: Option<f64>,
 }
 
 #[derive(Debug, Clone)]
@@ -63,6 +265,12 @@ pub enum MatchType {
     ColumnDescription,
 }
 
+impl Serialize for MatchType {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
 impl std::fmt::Display for MatchType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -72,4 +280,49 @@ impl std::fmt::Display for MatchType {
             MatchType::ColumnDescription => write!(f, "COL-DESC"),
         }
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn column(name: &str, fields: Vec<ColumnInfo>) -> ColumnInfo {
+        ColumnInfo {
+            name: name.to_string(),
+            column_type: if fields.is_empty() { "STRING" } else { "RECORD" }.to_string(),
+            fields,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_nested_column_paths() {
+        let metadata = TableMetadata {
+            table: TableInfo {
+                name: "events".to_string(),
+                project_id: "p".to_string(),
+                dataset_id: "d".to_string(),
+                ..Default::default()
+            },
+            columns: vec![
+                column("event_name", vec![]),
+                column("event_params", vec![
+                    column("key", vec![]),
+                    column("value", vec![column("string_value", vec![])]),
+                ]),
+            ],
+        };
+
+        let paths: Vec<String> = metadata.flatten_columns().into_iter().map(|(p, _)| p).collect();
+        assert_eq!(paths, vec![
+            "event_name",
+            "event_params",
+            "event_params.key",
+            "event_params.value",
+            "event_params.value.string_value",
+        ]);
+
+        assert_eq!(metadata.find_column("event_params.value.string_value").unwrap().name, "string_value");
+        assert!(metadata.find_column("event_params.missing").is_none());
+        assert!(metadata.find_column("key").is_none());
+    }
+}
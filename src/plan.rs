@@ -0,0 +1,137 @@
+use serde::Serialize;
+
+use crate::bigquery::{SchemaField, TableResource};
+use crate::models::{ColumnInfo, TableMetadata};
+
+/// A description that differs between the local catalog and BigQuery
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DescriptionChange {
+    /// Dotted column path, or `None` for the table description
+    pub column: Option<String>,
+    pub remote: Option<String>,
+    pub local: String,
+}
+
+/// Compare local descriptions with a remote table resource.
+///
+/// Only descriptions that are set locally are considered; an empty local description never
+/// clears a remote one. Columns that do not exist remotely are ignored.
+pub fn plan_descriptions(local: &TableMetadata, remote: &TableResource) -> Vec<DescriptionChange> {
+    let mut changes = Vec::new();
+
+    if let Some(ref desc) = local.table.description {
+        if remote.description.as_ref() != Some(desc) {
+            changes.push(DescriptionChange {
+                column: None,
+                remote: remote.description.clone(),
+                local: desc.clone(),
+            });
+        }
+    }
+
+    if let Some(ref schema) = remote.schema {
+        diff_fields(None, &local.columns, &schema.fields, &mut changes);
+    }
+
+    changes
+}
+
+fn diff_fields(prefix: Option<&str>, local: &[ColumnInfo], remote: &[SchemaField], changes: &mut Vec<DescriptionChange>) {
+    for field in remote {
+        let Some(column) = local.iter().find(|c| c.name == field.name) else { continue };
+        let path = match prefix {
+            Some(p) => format!("{}.{}", p, field.name),
+            None => field.name.clone(),
+        };
+
+        if let Some(ref desc) = column.description {
+            if field.description.as_ref() != Some(desc) {
+                changes.push(DescriptionChange {
+                    column: Some(path.clone()),
+                    remote: field.description.clone(),
+                    local: desc.clone(),
+                });
+            }
+        }
+
+        diff_fields(Some(&path), &column.fields, &field.fields, changes);
+    }
+}
+
+/// Build a `tables.patch` body carrying only the description changes.
+///
+/// `tables.patch` replaces the whole schema, so the remote schema is sent back unchanged
+/// apart from the descriptions, including field properties bq-meta doesn't model.
+pub fn build_patch(local: &TableMetadata, remote: &TableResource, changes: &[DescriptionChange]) -> serde_json::Value {
+    let mut patch = serde_json::Map::new();
+
+    if changes.iter().any(|c| c.column.is_none()) {
+        patch.insert("description".to_string(), serde_json::json!(local.table.description));
+    }
+
+    if changes.iter().any(|c| c.column.is_some()) {
+        if let Some(ref schema) = remote.schema {
+            let mut fields = schema.fields.clone();
+            apply_descriptions(&local.columns, &mut fields);
+            patch.insert("schema".to_string(), serde_json::json!({ "fields": fields }));
+        }
+    }
+
+    serde_json::Value::Object(patch)
+}
+
+fn apply_descriptions(local: &[ColumnInfo], remote: &mut [SchemaField]) {
+    for field in remote {
+        if let Some(column) = local.iter().find(|c| c.name == field.name) {
+            if column.description.is_some() {
+                field.description = column.description.clone();
+            }
+            apply_descriptions(&column.fields, &mut field.fields);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plan_and_patch() {
+        let remote: TableResource = serde_json::from_str(r#"{
+            "tableReference": {"projectId": "p", "datasetId": "d", "tableId": "t"},
+            "description": "Same",
+            "schema": {"fields": [
+                {"name": "id", "type": "STRING", "description": "Old", "maxLength": "36",
+                 "defaultValueExpression": "GENERATE_UUID()"},
+                {"name": "keep", "type": "STRING", "description": "Remote only"},
+                {"name": "rec", "type": "RECORD", "fields": [{"name": "x", "type": "INT64"}]}
+            ]}
+        }"#).unwrap();
+        let local: TableMetadata = serde_yaml::from_str(r#"
+table: {name: t, project_id: p, dataset_id: d, description: Same}
+columns:
+  - {name: id, type: STRING, description: New}
+  - {name: keep, type: STRING}
+  - name: rec
+    type: RECORD
+    fields: [{name: x, type: INT64, description: Nested}]
+  - {name: local_only, type: STRING, description: Ignored}
+"#).unwrap();
+
+        let changes = plan_descriptions(&local, &remote);
+        assert_eq!(changes.len(), 2);
+        assert_eq!(changes[0].column.as_deref(), Some("id"));
+        assert_eq!(changes[0].remote.as_deref(), Some("Old"));
+        assert_eq!(changes[1].column.as_deref(), Some("rec.x"));
+
+        let patch = build_patch(&local, &remote, &changes);
+        assert!(patch.get("description").is_none());
+        let fields = &patch["schema"]["fields"];
+        assert_eq!(fields[0]["description"], "New");
+        assert_eq!(fields[0]["maxLength"], "36");
+        assert_eq!(fields[0]["defaultValueExpression"], "GENERATE_UUID()");
+        assert_eq!(fields[1]["description"], "Remote only");
+        assert_eq!(fields[2]["fields"][0]["description"], "Nested");
+        assert_eq!(fields.as_array().unwrap().len(), 3);
+    }
+}
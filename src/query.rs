@@ -0,0 +1,403 @@
+use std::fmt;
+
+use crate::search::fold;
+use crate::models::{ColumnInfo, TableMetadata};
+
+/// Field a query term is restricted to, as in `column:user_id`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Field {
+    Table,
+    Desc,
+    Column,
+    ColumnDesc,
+    Type,
+    Mode,
+    Project,
+    Dataset,
+}
+
+impl Field {
+    fn parse(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "table" | "name" => Some(Field::Table),
+            "desc" | "description" => Some(Field::Desc),
+            "column" | "col" => Some(Field::Column),
+            "col-desc" | "coldesc" => Some(Field::ColumnDesc),
+            "type" => Some(Field::Type),
+            "mode" => Some(Field::Mode),
+            "project" => Some(Field::Project),
+            "dataset" => Some(Field::Dataset),
+            _ => None,
+        }
+    }
+
+    /// Whether the field is a property of a single column
+    fn is_column(self) -> bool {
+        matches!(self, Field::Column | Field::ColumnDesc | Field::Type | Field::Mode)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    /// `field:value`, or a bare value matched against table and column names and descriptions
+    Term { field: Option<Field>, value: String },
+    Not(Box<Expr>),
+    And(Vec<Expr>),
+    Or(Vec<Expr>),
+}
+
+impl Expr {
+    /// Evaluate the query against a table.
+    ///
+    /// Returns the paths of the columns that satisfied column terms, or `None` when the table
+    /// doesn't match. Column terms joined by AND must hold for the same column, so
+    /// `column:user_id type:INT64` finds an INT64 `user_id`; a negated column term such as
+    /// `-column:tmp` means no column matches.
+    ///
+    /// `case_sensitive` applies to names and descriptions; types, modes, projects and datasets
+    /// always compare case-insensitively.
+    pub fn matches(&self, project_id: &str, dataset_id: &str, metadata: &TableMetadata, case_sensitive: bool) -> Option<Vec<String>> {
+        match self {
+            Expr::Term { field: Some(field), value } if field.is_column() => {
+                let columns = matching_columns(metadata, |column| column_term_matches(*field, value, column, case_sensitive));
+                (!columns.is_empty()).then_some(columns)
+            }
+            Expr::Term { field: Some(field), value } => {
+                let text = match field {
+                    Field::Table => &metadata.table.name,
+                    Field::Desc => metadata.table.description.as_deref().unwrap_or(""),
+                    Field::Project => return project_id.eq_ignore_ascii_case(value).then(Vec::new),
+                    Field::Dataset => return dataset_id.eq_ignore_ascii_case(value).then(Vec::new),
+                    _ => unreachable!("column fields are handled above"),
+                };
+                contains(text, value, case_sensitive).then(Vec::new)
+            }
+            Expr::Term { field: None, value } => {
+                let columns = matching_columns(metadata, |column| {
+                    contains(&column.name, value, case_sensitive)
+                        || contains(column.description.as_deref().unwrap_or(""), value, case_sensitive)
+                });
+                let table_match = contains(&metadata.table.name, value, case_sensitive)
+                    || contains(metadata.table.description.as_deref().unwrap_or(""), value, case_sensitive);
+                (table_match || !columns.is_empty()).then_some(columns)
+            }
+            Expr::Not(expr) => expr.matches(project_id, dataset_id, metadata, case_sensitive).is_none().then(Vec::new),
+            Expr::Or(exprs) => {
+                let mut matched = None;
+                for expr in exprs {
+                    if let Some(columns) = expr.matches(project_id, dataset_id, metadata, case_sensitive) {
+                        merge_columns(matched.get_or_insert_with(Vec::new), columns);
+                    }
+                }
+                matched
+            }
+            Expr::And(exprs) => {
+                let (correlated, rest): (Vec<&Expr>, Vec<&Expr>) = exprs.iter()
+                    .partition(|e| !matches!(e, Expr::Not(_)) && e.is_column_only());
+
+                let mut matched = Vec::new();
+                if !correlated.is_empty() {
+                    matched = matching_columns(metadata, |column| {
+                        correlated.iter().all(|e| e.matches_column(column, case_sensitive))
+                    });
+                    if matched.is_empty() {
+                        return None;
+                    }
+                }
+                for expr in rest {
+                    merge_columns(&mut matched, expr.matches(project_id, dataset_id, metadata, case_sensitive)?);
+                }
+                Some(matched)
+            }
+        }
+    }
+
+    fn is_column_only(&self) -> bool {
+        match self {
+            Expr::Term { field, .. } => field.is_some_and(Field::is_column),
+            Expr::Not(expr) => expr.is_column_only(),
+            Expr::And(exprs) | Expr::Or(exprs) => exprs.iter().all(Expr::is_column_only),
+        }
+    }
+
+    /// Evaluate a column-only expression against one column
+    fn matches_column(&self, column: &ColumnInfo, case_sensitive: bool) -> bool {
+        match self {
+            Expr::Term { field, value } => field.is_some_and(|f| column_term_matches(f, value, column, case_sensitive)),
+            Expr::Not(expr) => !expr.matches_column(column, case_sensitive),
+            Expr::And(exprs) => exprs.iter().all(|e| e.matches_column(column, case_sensitive)),
+            Expr::Or(exprs) => exprs.iter().any(|e| e.matches_column(column, case_sensitive)),
+        }
+    }
+}
+
+fn contains(text: &str, value: &str, case_sensitive: bool) -> bool {
+    fold(text, case_sensitive).contains(&fold(value, case_sensitive))
+}
+
+fn column_term_matches(field: Field, value: &str, column: &ColumnInfo, case_sensitive: bool) -> bool {
+    match field {
+        Field::Column => contains(&column.name, value, case_sensitive),
+        Field::ColumnDesc => contains(column.description.as_deref().unwrap_or(""), value, case_sensitive),
+        Field::Type => column.column_type.eq_ignore_ascii_case(value),
+        Field::Mode => column.mode.as_deref().unwrap_or("NULLABLE").eq_ignore_ascii_case(value),
+        _ => false,
+    }
+}
+
+fn matching_columns(metadata: &TableMetadata, predicate: impl Fn(&ColumnInfo) -> bool) -> Vec<String> {
+    metadata.flatten_columns().into_iter()
+        .filter(|(_, column)| predicate(column))
+        .map(|(path, _)| path)
+        .collect()
+}
+
+fn merge_columns(into: &mut Vec<String>, columns: Vec<String>) {
+    for column in columns {
+        if !into.contains(&column) {
+            into.push(column);
+        }
+    }
+}
+
+/// Query syntax error, rendered with a caret under the offending token
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueryError {
+    pub message: String,
+    /// Byte range of the offending token in the query
+    pub start: usize,
+    pub end: usize,
+    query: String,
+}
+
+impl fmt::Display for QueryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let column = self.query[..self.start].chars().count();
+        let width = self.query[self.start..self.end].chars().count().max(1);
+        writeln!(f, "{} at column {}", self.message, column + 1)?;
+        writeln!(f, "  {}", self.query)?;
+        write!(f, "  {}{}", " ".repeat(column), "^".repeat(width))
+    }
+}
+
+impl std::error::Error for QueryError {}
+
+#[derive(Debug, Clone, PartialEq)]
+enum TokenKind {
+    Term { field: Option<Field>, value: String },
+    LParen,
+    RParen,
+    And,
+    Or,
+    Not,
+}
+
+#[derive(Debug, Clone)]
+struct Token {
+    kind: TokenKind,
+    start: usize,
+    end: usize,
+}
+
+struct Parser<'a> {
+    query: &'a str,
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+/// Parse a query such as `column:user_id type:INT64 desc:"billing account" -dataset:tmp`.
+///
+/// Terms are `field:value` or a bare value; values may be quoted phrases. Terms next to each
+/// other are joined by AND; `OR`, `AND`, `NOT` (or a leading `-`) and parentheses combine them.
+pub fn parse_query(query: &str) -> Result<Expr, QueryError> {
+    let mut parser = Parser { query, tokens: Vec::new(), pos: 0 };
+    parser.tokenize()?;
+    if parser.tokens.is_empty() {
+        return Err(parser.error("Empty query", 0, query.len()));
+    }
+
+    let expr = parser.parse_or()?;
+    if let Some(token) = parser.peek() {
+        return Err(parser.error("Unexpected ')'", token.start, token.end));
+    }
+    Ok(expr)
+}
+
+impl Parser<'_> {
+    fn error(&self, message: &str, start: usize, end: usize) -> QueryError {
+        QueryError { message: message.to_string(), start, end, query: self.query.to_string() }
+    }
+
+    fn tokenize(&mut self) -> Result<(), QueryError> {
+        let mut chars = self.query.char_indices().peekable();
+        while let Some(&(start, c)) = chars.peek() {
+            if c.is_whitespace() {
+                chars.next();
+                continue;
+            }
+            let single = match c {
+                '(' => Some(TokenKind::LParen),
+                ')' => Some(TokenKind::RParen),
+                '-' => Some(TokenKind::Not),
+                _ => None,
+            };
+            if let Some(kind) = single {
+                chars.next();
+                if kind == TokenKind::Not && chars.peek().is_none_or(|(_, next)| next.is_whitespace()) {
+                    return Err(self.error("Expected a term after '-'", start, start + 1));
+                }
+                self.tokens.push(Token { kind, start, end: start + 1 });
+                continue;
+            }
+
+            let mut field: Option<(String, usize)> = None;
+            let mut value = String::new();
+            let mut quoted = false;
+            while let Some(&(i, ch)) = chars.peek() {
+                if ch.is_whitespace() || ch == '(' || ch == ')' {
+                    break;
+                }
+                chars.next();
+                if ch == '"' {
+                    loop {
+                        match chars.next() {
+                            Some((_, '"')) => break,
+                            Some((_, ch)) => value.push(ch),
+                            None => return Err(self.error("Unterminated quote", i, self.query.len())),
+                        }
+                    }
+                    quoted = true;
+                } else if ch == ':' && field.is_none() && !quoted {
+                    field = Some((std::mem::take(&mut value), i));
+                } else {
+                    value.push(ch);
+                }
+            }
+            let end = chars.peek().map_or(self.query.len(), |&(i, _)| i);
+
+            let kind = match field {
+                Some((name, colon)) => {
+                    let field = Field::parse(&name).ok_or_else(|| {
+                        self.error(&format!("Unknown field '{}'", name), start, colon)
+                    })?;
+                    if value.is_empty() {
+                        return Err(self.error(&format!("Missing value after '{}:'", name), start, end));
+                    }
+                    TokenKind::Term { field: Some(field), value }
+                }
+                None if !quoted && value == "AND" => TokenKind::And,
+                None if !quoted && value == "OR" => TokenKind::Or,
+                None if !quoted && value == "NOT" => TokenKind::Not,
+                None => TokenKind::Term { field: None, value },
+            };
+            self.tokens.push(Token { kind, start, end });
+        }
+        Ok(())
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn parse_or(&mut self) -> Result<Expr, QueryError> {
+        let mut exprs = vec![self.parse_and()?];
+        while self.peek().is_some_and(|t| t.kind == TokenKind::Or) {
+            self.next();
+            exprs.push(self.parse_and()?);
+        }
+        Ok(if exprs.len() == 1 { exprs.remove(0) } else { Expr::Or(exprs) })
+    }
+
+    fn parse_and(&mut self) -> Result<Expr, QueryError> {
+        let mut exprs = vec![self.parse_unary()?];
+        loop {
+            match self.peek().map(|t| &t.kind) {
+                None | Some(TokenKind::RParen) | Some(TokenKind::Or) => break,
+                Some(TokenKind::And) => {
+                    self.next();
+                }
+                _ => {}
+            }
+            exprs.push(self.parse_unary()?);
+        }
+        Ok(if exprs.len() == 1 { exprs.remove(0) } else { Expr::And(exprs) })
+    }
+
+    fn parse_unary(&mut self) -> Result<Expr, QueryError> {
+        if self.peek().is_some_and(|t| t.kind == TokenKind::Not) {
+            self.next();
+            return Ok(Expr::Not(Box::new(self.parse_unary()?)));
+        }
+        self.parse_primary()
+    }
+
+    fn parse_primary(&mut self) -> Result<Expr, QueryError> {
+        let Some(token) = self.next() else {
+            let end = self.query.len();
+            let (start, end) = self.tokens.last().map_or((0, end), |t| (t.start, t.end));
+            return Err(self.error("Expected a term after this", start, end));
+        };
+        match token.kind {
+            TokenKind::Term { field, value } => Ok(Expr::Term { field, value }),
+            TokenKind::LParen => {
+                let expr = self.parse_or()?;
+                match self.next() {
+                    Some(Token { kind: TokenKind::RParen, .. }) => Ok(expr),
+                    _ => Err(self.error("Unclosed '('", token.start, token.end)),
+                }
+            }
+            TokenKind::RParen => Err(self.error("Unexpected ')'", token.start, token.end)),
+            TokenKind::And | TokenKind::Or => Err(self.error("Expected a term before operator", token.start, token.end)),
+            TokenKind::Not => unreachable!("handled by parse_unary"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{ColumnInfo, TableInfo};
+
+    fn column(name: &str, column_type: &str) -> ColumnInfo {
+        ColumnInfo { name: name.to_string(), column_type: column_type.to_string(), ..Default::default() }
+    }
+
+    #[test]
+    fn test_parse_and_evaluate() {
+        let metadata = TableMetadata {
+            table: TableInfo {
+                name: "invoices".to_string(),
+                description: Some("Monthly billing invoices".to_string()),
+                ..Default::default()
+            },
+            columns: vec![column("user_id", "STRING"), column("amount", "INT64")],
+        };
+        let matches = |query: &str| parse_query(query).unwrap().matches("p", "sales", &metadata, false);
+
+        assert_eq!(matches("column:user_id desc:billing -dataset:tmp"), Some(vec!["user_id".to_string()]));
+        // Column terms must hold for the same column
+        assert_eq!(matches("column:user_id type:INT64"), None);
+        assert_eq!(matches("column:user_id (type:INT64 OR type:STRING)"), Some(vec!["user_id".to_string()]));
+        assert_eq!(matches("-column:amount"), None);
+        assert_eq!(matches("desc:\"billing invoices\" OR table:orders"), Some(vec![]));
+        assert_eq!(matches("NOT dataset:sales"), None);
+
+        let case_sensitive = |query: &str| parse_query(query).unwrap().matches("p", "SALES", &metadata, true);
+        assert_eq!(case_sensitive("column:USER_ID"), None);
+        assert_eq!(case_sensitive("column:user_id type:string dataset:sales"), Some(vec!["user_id".to_string()]));
+        assert_eq!(case_sensitive("Billing"), None);
+
+        let error = parse_query("colum:user_id type:INT64").unwrap_err();
+        assert_eq!((error.start, error.end), (0, 5));
+        assert!(error.to_string().ends_with("  colum:user_id type:INT64\n  ^^^^^"));
+        assert_eq!(parse_query("(column:id OR type:INT64").unwrap_err().start, 0);
+        assert_eq!(parse_query("desc:\"billing").unwrap_err().message, "Unterminated quote");
+        assert_eq!(parse_query("type: id").unwrap_err().message, "Missing value after 'type:'");
+    }
+}
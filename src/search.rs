@@ -2,9 +2,13 @@ use anyhow::Result;
 use regex::Regex;
 use std::collections::HashMap;
 
-use crate::models::{SearchResult, MatchType};
-use crate::storage::{list_tables, load_table_metadata};
+use crate::index::{normalize, SearchIndex};
+use crate::migration;
+use crate::models::{SearchResult, MatchType, TableMetadata};
+use crate::query::parse_query;
+use crate::storage::{check_scope, open_storage};
 
+#[derive(Default)]
 pub struct SearchOptions {
     pub pattern: String,
     pub regex: bool,
@@ -15,30 +19,87 @@ pub struct SearchOptions {
     pub search_column_desc: bool,
     pub project_filter: Option<String>,
     pub dataset_filter: Option<String>,
+    /// Maximum number of results to return, best matches first
+    pub limit: Option<usize>,
+    /// Also accept approximate matches whose similarity (0.0-1.0) is at least this threshold
+    pub fuzzy: Option<f64>,
+    /// Treat `pattern` as a structured query (see `query::parse_query`)
+    pub query: bool,
 }
 
-impl Default for SearchOptions {
-    fn default() -> Self {
-        Self {
-            pattern: String::new(),
-            regex: false,
-            case_sensitive: false,
-            search_all: false,
-            search_table_desc: false,
-            search_column_name: false,
-            search_column_desc: false,
-            project_filter: None,
-            dataset_filter: None,
+/// A table to search as `(project, dataset, table, metadata)`
+type Candidate = (String, String, String, TableMetadata);
+
+/// Results of a search, and the tables it had to leave out
+#[derive(Debug, Default)]
+pub struct SearchOutcome {
+    pub results: Vec<SearchResult>,
+    /// Tables in scope written by a newer bq-meta, as `project.dataset.table`
+    pub newer_format: Vec<String>,
+}
+
+/// Tables to search, and the tables in scope skipped because a newer bq-meta wrote them.
+///
+/// Uses the persistent index when it has been built (refreshing it from file mtimes first),
+/// otherwise falls back to loading every table from storage.
+fn candidate_tables(options: &SearchOptions) -> Result<(Vec<Candidate>, Vec<String>)> {
+    let (project_filter, dataset_filter) = (options.project_filter.as_deref(), options.dataset_filter.as_deref());
+    // Validated up front so the index and storage paths accept the same filters
+    check_scope(project_filter, dataset_filter)?;
+    let in_scope = |project_id: &str, dataset_id: &str| {
+        project_filter.is_none_or(|p| p == project_id) && dataset_filter.is_none_or(|d| d == dataset_id)
+    };
+
+    if let Some(mut index) = SearchIndex::load()? {
+        if index.update()? > 0 {
+            index.save()?;
         }
+        let newer_format = index.newer_format().iter()
+            .filter(|(p, d, _)| in_scope(p, d))
+            .map(|(p, d, t)| format!("{}.{}.{}", p, d, t))
+            .collect();
+        // Regex, fuzzy and query patterns can't be narrowed by exact tokens
+        let exact = !options.regex && options.fuzzy.is_none() && !options.query;
+        let pattern = exact.then_some(options.pattern.as_str());
+        let tables = index
+            .candidates(pattern, project_filter, dataset_filter)
+            .into_iter()
+            .map(|e| (e.project_id.clone(), e.dataset_id.clone(), e.table_name.clone(), e.metadata.clone()))
+            .collect();
+        return Ok((tables, newer_format));
     }
+
+    let storage = open_storage()?;
+    let mut tables = Vec::new();
+    let mut newer_format = Vec::new();
+    for (project_id, dataset_id, table_name) in storage.list(project_filter, dataset_filter)? {
+        match storage.load(&project_id, &dataset_id, &table_name) {
+            Ok(metadata) => tables.push((project_id, dataset_id, table_name, metadata)),
+            Err(err) if migration::is_newer_format(&err) => {
+                newer_format.push(format!("{}.{}.{}", project_id, dataset_id, table_name));
+            }
+            // Skip tables we can't load
+            Err(_) => {}
+        }
+    }
+    Ok((tables, newer_format))
 }
 
-pub fn search_tables(options: &SearchOptions) -> Result<Vec<SearchResult>> {
-    let tables = list_tables(
-        options.project_filter.as_deref(),
-        options.dataset_filter.as_deref(),
-    )?;
+pub fn search_tables(options: &SearchOptions) -> Result<SearchOutcome> {
+    if !options.query && options.fuzzy.is_some_and(|threshold| !(0.0..=1.0).contains(&threshold)) {
+        anyhow::bail!("Fuzzy threshold must be between 0.0 and 1.0");
+    }
+    let (tables, newer_format) = candidate_tables(options)?;
+    let results = if options.query {
+        query_tables(options, tables)?
+    } else {
+        match_tables(options, tables)?
+    };
+    Ok(SearchOutcome { results, newer_format })
+}
 
+/// Matches of the search pattern in the given tables, best first
+fn match_tables(options: &SearchOptions, tables: Vec<Candidate>) -> Result<Vec<SearchResult>> {
     let mut results = Vec::new();
     let regex = if options.regex {
         Some(if options.case_sensitive {
@@ -49,63 +110,80 @@ pub fn search_tables(options: &SearchOptions) -> Result<Vec<SearchResult>> {
     } else {
         None
     };
+    // Returns `(score, similarity)` when `text` matches; similarity is only set for fuzzy matches
+    let check = |match_type: &MatchType, text: &str| -> Option<(f64, Option<f64>)> {
+        if matches_pattern(text, &options.pattern, &regex, options.case_sensitive) {
+            let score = score_match(match_type, text, &options.pattern, &regex, options.case_sensitive);
+            return Some((score, options.fuzzy.map(|_| 1.0)));
+        }
+        let threshold = options.fuzzy?;
+        let similarity = fuzzy_similarity(text, &options.pattern, options.case_sensitive);
+        (similarity >= threshold)
+            .then(|| (fuzzy_score(match_type, text, &options.pattern, similarity), Some(similarity)))
+    };
 
-    for (project_id, dataset_id, table_name) in tables {
+    for (project_id, dataset_id, table_name, metadata) in tables {
         let table_path = format!("{}.{}.{}", project_id, dataset_id, table_name);
-        
-        // Load table metadata
-        let metadata = match load_table_metadata(&project_id, &dataset_id, &table_name) {
-            Ok(m) => m,
-            Err(_) => continue, // Skip if we can't load metadata
-        };
 
         // Search table name (default behavior)
         if !options.search_all && !options.search_table_desc && !options.search_column_name && !options.search_column_desc {
-            if matches_pattern(&table_name, &options.pattern, &regex, options.case_sensitive) {
+            if let Some((score, similarity)) = check(&MatchType::TableName, &table_name) {
                 results.push(SearchResult {
                     table_path: table_path.clone(),
                     match_type: MatchType::TableName,
                     matched_content: table_name.clone(),
                     context: None,
+                    column: None,
+                    score,
+                    similarity,
                 });
             }
         } else {
             // Search based on specific options
             if options.search_all || options.search_table_desc {
                 // Search table name
-                if matches_pattern(&table_name, &options.pattern, &regex, options.case_sensitive) {
+                if let Some((score, similarity)) = check(&MatchType::TableName, &table_name) {
                     results.push(SearchResult {
                         table_path: table_path.clone(),
                         match_type: MatchType::TableName,
                         matched_content: table_name.clone(),
                         context: None,
+                        column: None,
+                        score,
+                        similarity,
                     });
                 }
 
                 // Search table description
                 if let Some(ref desc) = metadata.table.description {
-                    if matches_pattern(desc, &options.pattern, &regex, options.case_sensitive) {
+                    if let Some((score, similarity)) = check(&MatchType::TableDescription, desc) {
                         results.push(SearchResult {
                             table_path: table_path.clone(),
                             match_type: MatchType::TableDescription,
                             matched_content: desc.clone(),
                             context: None,
+                            column: None,
+                            score,
+                            similarity,
                         });
                     }
                 }
             }
 
             if options.search_all || options.search_column_name || options.search_column_desc {
-                // Search columns
-                for column in &metadata.columns {
+                // Search columns, including nested RECORD fields
+                for (column_path, column) in metadata.flatten_columns() {
                     // Search column name
                     if options.search_all || options.search_column_name {
-                        if matches_pattern(&column.name, &options.pattern, &regex, options.case_sensitive) {
+                        if let Some((score, similarity)) = check(&MatchType::ColumnName, &column.name) {
                             results.push(SearchResult {
                                 table_path: table_path.clone(),
                                 match_type: MatchType::ColumnName,
                                 matched_content: column.name.clone(),
-                                context: Some(format!("Column: {}", column.name)),
+                                context: Some(format!("Column: {}", column_path)),
+                                column: Some(column_path.clone()),
+                                score,
+                                similarity,
                             });
                         }
                     }
@@ -113,12 +191,15 @@ pub fn search_tables(options: &SearchOptions) -> Result<Vec<SearchResult>> {
                     // Search column description
                     if options.search_all || options.search_column_desc {
                         if let Some(ref desc) = column.description {
-                            if matches_pattern(desc, &options.pattern, &regex, options.case_sensitive) {
+                            if let Some((score, similarity)) = check(&MatchType::ColumnDescription, desc) {
                                 results.push(SearchResult {
                                     table_path: table_path.clone(),
                                     match_type: MatchType::ColumnDescription,
                                     matched_content: desc.clone(),
-                                    context: Some(format!("Column: {}", column.name)),
+                                    context: Some(format!("Column: {}", column_path)),
+                                    column: Some(column_path.clone()),
+                                    score,
+                                    similarity,
                                 });
                             }
                         }
@@ -128,26 +209,142 @@ pub fn search_tables(options: &SearchOptions) -> Result<Vec<SearchResult>> {
         }
     }
 
-    // Remove duplicates and sort
+    // Remove duplicates and rank
     let mut unique_results: HashMap<String, SearchResult> = HashMap::new();
     for result in results {
-        let key = format!("{}:{}:{}", result.table_path, result.match_type, result.matched_content);
+        let key = format!("{}:{}:{}:{}", result.table_path, result.match_type, result.matched_content,
+                          result.context.as_deref().unwrap_or(""));
         unique_results.entry(key).or_insert(result);
     }
 
     let mut final_results: Vec<SearchResult> = unique_results.into_values().collect();
-    final_results.sort_by(|a, b| a.table_path.cmp(&b.table_path));
+    final_results.sort_by(|a, b| {
+        b.score.total_cmp(&a.score)
+            .then_with(|| a.table_path.cmp(&b.table_path))
+            .then_with(|| a.context.cmp(&b.context))
+    });
+    if let Some(limit) = options.limit {
+        final_results.truncate(limit);
+    }
 
     Ok(final_results)
 }
 
+/// Tables matching a structured query, one result per table listing the columns that matched
+fn query_tables(options: &SearchOptions, tables: Vec<Candidate>) -> Result<Vec<SearchResult>> {
+    let expr = parse_query(&options.pattern)?;
+
+    let mut results = Vec::new();
+    for (project_id, dataset_id, table_name, metadata) in tables {
+        let Some(columns) = expr.matches(&project_id, &dataset_id, &metadata, options.case_sensitive) else { continue };
+        results.push(SearchResult {
+            table_path: format!("{}.{}.{}", project_id, dataset_id, table_name),
+            match_type: MatchType::TableName,
+            matched_content: table_name,
+            context: (!columns.is_empty()).then(|| format!("Columns: {}", columns.join(", "))),
+            column: None,
+            // Queries filter rather than rank; more matching columns first
+            score: columns.len() as f64,
+            similarity: None,
+        });
+    }
+
+    results.sort_by(|a, b| b.score.total_cmp(&a.score).then_with(|| a.table_path.cmp(&b.table_path)));
+    if let Some(limit) = options.limit {
+        results.truncate(limit);
+    }
+    Ok(results)
+}
+
+/// Relevance of a match: the weight of where it matched, boosted for exact and prefix
+/// matches, plus the fraction of the text the match covers so tighter hits rank first.
+///
+/// Tiers are spaced so an exact table name beats a prefix, a table name beats a column
+/// name, and any name beats a description.
+fn score_match(match_type: &MatchType, text: &str, pattern: &str, regex: &Option<Regex>, case_sensitive: bool) -> f64 {
+    let weight = match_weight(match_type);
+
+    let (text, found) = match regex {
+        Some(re) => (text.to_string(), re.find(text).map(|m| (m.start(), m.len()))),
+        None => {
+            let (text, pattern) = (fold(text, case_sensitive), fold(pattern, case_sensitive));
+            let found = text.find(&pattern).map(|start| (start, pattern.len()));
+            (text, found)
+        }
+    };
+    let Some((start, len)) = found else { return 0.0 };
+
+    let boost = if start == 0 && len == text.len() {
+        2.0
+    } else if start == 0 {
+        1.5
+    } else {
+        1.0
+    };
+    let coverage = len as f64 / text.len().max(1) as f64;
+
+    round_score(weight * boost + coverage)
+}
+
+/// Score of an approximate match: the unboosted location weight scaled by similarity, so
+/// fuzzy hits rank below exact hits in the same place.
+fn fuzzy_score(match_type: &MatchType, text: &str, pattern: &str, similarity: f64) -> f64 {
+    let coverage = (pattern.chars().count() as f64 / text.chars().count().max(1) as f64).min(1.0);
+    round_score((match_weight(match_type) + coverage) * similarity)
+}
+
+fn match_weight(match_type: &MatchType) -> f64 {
+    match match_type {
+        MatchType::TableName => 10.0,
+        MatchType::ColumnName => 5.0,
+        MatchType::TableDescription => 2.0,
+        MatchType::ColumnDescription => 1.0,
+    }
+}
+
+/// Round to three decimals to keep JSON output readable
+fn round_score(score: f64) -> f64 {
+    (score * 1000.0).round() / 1000.0
+}
+
+/// Similarity between `pattern` and its closest substring of `text`, as
+/// `1 - edit_distance / pattern_length`. `custmer` against `customer_id` is 0.857.
+fn fuzzy_similarity(text: &str, pattern: &str, case_sensitive: bool) -> f64 {
+    let text: Vec<char> = fold(text, case_sensitive).chars().collect();
+    let pattern: Vec<char> = fold(pattern, case_sensitive).chars().collect();
+    if pattern.is_empty() {
+        return 1.0;
+    }
+
+    // Levenshtein distance where the match may start and end anywhere in the text
+    let mut prev: Vec<usize> = (0..=pattern.len()).collect();
+    let mut best = pattern.len();
+    for &c in &text {
+        let mut cur = vec![0; pattern.len() + 1];
+        for j in 1..=pattern.len() {
+            let cost = usize::from(pattern[j - 1] != c);
+            cur[j] = (prev[j - 1] + cost).min(prev[j] + 1).min(cur[j - 1] + 1);
+        }
+        best = best.min(cur[pattern.len()]);
+        prev = cur;
+    }
+
+    round_score(1.0 - best as f64 / pattern.len() as f64)
+}
+
+/// NFKC-normalize text for comparison, lowercasing it unless the search is case sensitive
+pub(crate) fn fold(text: &str, case_sensitive: bool) -> String {
+    let text = normalize(text);
+    if case_sensitive { text } else { text.to_lowercase() }
+}
+
+/// Regular expressions match the raw text; plain patterns are compared after NFKC
+/// normalization, so full-width `ＩＤ` matches `id` and half-width `ｶﾅ` matches `カナ`.
 fn matches_pattern(text: &str, pattern: &str, regex: &Option<Regex>, case_sensitive: bool) -> bool {
     if let Some(ref re) = regex {
         re.is_match(text)
-    } else if case_sensitive {
-        text.contains(pattern)
     } else {
-        text.to_lowercase().contains(&pattern.to_lowercase())
+        fold(text, case_sensitive).contains(&fold(pattern, case_sensitive))
     }
 }
 
@@ -166,12 +363,47 @@ mod tests {
         assert!(matches_pattern("user_events", "user", &None, true));
         assert!(!matches_pattern("user_events", "USER", &None, true));
 
+        // Full-width and half-width forms are folded before comparing
+        assert!(matches_pattern("ユーザーＩＤ", "ユーザーid", &None, false));
+        assert!(matches_pattern("ｶﾀｶﾅの説明", "カタカナ", &None, true));
+        assert!(matches_pattern("購入履歴テーブル", "履歴", &None, false));
+
         // Regex match
         let regex = Regex::new(r"^user_.*").unwrap();
         assert!(matches_pattern("user_events", "", &Some(regex), false));
         
         let regex = Regex::new(r"events$").unwrap();
-        assert!(matches_pattern("user_events", "", &Some(regex), false));
+        assert!(matches_pattern("user_events", "", &Some(regex.clone()), false));
         assert!(!matches_pattern("user_data", "", &Some(regex), false));
     }
+
+    #[test]
+    fn test_score_match_ranking() {
+        let score = |match_type, text| score_match(&match_type, text, "users", &None, false);
+
+        let exact = score(MatchType::TableName, "Users");
+        let prefix = score(MatchType::TableName, "users_daily");
+        let column = score(MatchType::ColumnName, "users");
+        let desc = score(MatchType::TableDescription, "users");
+        assert!(exact > prefix);
+        assert!(prefix > column);
+        assert!(column > desc);
+        assert!(desc > score(MatchType::ColumnDescription, "users"));
+
+        // Shorter text with the same kind of match ranks higher
+        assert!(score(MatchType::ColumnName, "all_users") > score(MatchType::ColumnName, "all_active_users"));
+    }
+
+    #[test]
+    fn test_fuzzy_similarity() {
+        assert_eq!(fuzzy_similarity("customer_id", "customer", false), 1.0);
+        assert_eq!(fuzzy_similarity("customer_id", "custmer_id", false), 0.9);
+        assert_eq!(fuzzy_similarity("Customer ID of the order", "custmer", false), 0.857);
+        assert!(fuzzy_similarity("Customer", "custmer", true) < 0.8);
+        assert!(fuzzy_similarity("order_total", "custmer", false) < 0.5);
+
+        // An approximate hit ranks below an exact one in the same place
+        let exact = score_match(&MatchType::ColumnName, "customer_id", "customer_id", &None, false);
+        assert!(fuzzy_score(&MatchType::ColumnName, "customer_id", "custmer_id", 0.9) < exact);
+    }
 }
\ No newline at end of file
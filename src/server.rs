@@ -0,0 +1,350 @@
+use anyhow::Result;
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, put};
+use axum::{Json, Router};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::net::SocketAddr;
+
+use crate::models::TableMetadata;
+use crate::search::{search_tables, SearchOptions};
+use crate::storage::{self, open_storage, update_table_metadata, CatalogBusy, Storage};
+
+#[derive(Clone)]
+struct AppState {
+    writable: bool,
+}
+
+/// Error response rendered as `{"error": "..."}`
+#[derive(Debug)]
+struct ApiError(StatusCode, String);
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.0, Json(serde_json::json!({ "error": self.1 }))).into_response()
+    }
+}
+
+impl std::fmt::Display for ApiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.1)
+    }
+}
+
+impl std::error::Error for ApiError {}
+
+impl From<anyhow::Error> for ApiError {
+    fn from(err: anyhow::Error) -> Self {
+        if err.is::<CatalogBusy>() {
+            return ApiError(StatusCode::SERVICE_UNAVAILABLE, format!("{:#}", err));
+        }
+        // An ApiError raised inside a storage callback keeps its status
+        err.downcast::<ApiError>()
+            .unwrap_or_else(|err| ApiError(StatusCode::INTERNAL_SERVER_ERROR, format!("{:#}", err)))
+    }
+}
+
+type ApiResult<T> = std::result::Result<Json<T>, ApiError>;
+
+#[derive(Deserialize)]
+struct ListQuery {
+    project: Option<String>,
+    dataset: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct SearchQuery {
+    q: String,
+    /// `table` (default), `all`, `desc`, `column` or `col-desc`, as in the `search` command flags,
+    /// or `query` to parse `q` as a structured query
+    scope: Option<String>,
+    #[serde(default)]
+    regex: bool,
+    #[serde(default)]
+    case_sensitive: bool,
+    project: Option<String>,
+    dataset: Option<String>,
+    limit: Option<usize>,
+}
+
+#[derive(Deserialize)]
+struct DescriptionBody {
+    description: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct TablePatch {
+    /// New table description; omit to leave unchanged, `null` to clear
+    #[serde(default, deserialize_with = "deserialize_some")]
+    description: Option<Option<String>>,
+    /// Column descriptions keyed by dotted column path
+    #[serde(default)]
+    columns: BTreeMap<String, Option<String>>,
+}
+
+/// Distinguish an explicit `null` from a missing field
+fn deserialize_some<'de, D: serde::Deserializer<'de>>(deserializer: D) -> std::result::Result<Option<Option<String>>, D::Error> {
+    Option::<String>::deserialize(deserializer).map(Some)
+}
+
+fn normalize(description: Option<String>) -> Option<String> {
+    description.filter(|d| !d.is_empty())
+}
+
+/// Build search options from the `scope` query parameter
+fn search_options(query: SearchQuery) -> std::result::Result<SearchOptions, ApiError> {
+    let mut options = SearchOptions {
+        pattern: query.q,
+        regex: query.regex,
+        case_sensitive: query.case_sensitive,
+        project_filter: query.project,
+        dataset_filter: query.dataset,
+        limit: query.limit,
+        ..Default::default()
+    };
+
+    match query.scope.as_deref().unwrap_or("table") {
+        "table" => {}
+        "all" => options.search_all = true,
+        "desc" => options.search_table_desc = true,
+        "column" => options.search_column_name = true,
+        "col-desc" => options.search_column_desc = true,
+        "query" => options.query = true,
+        scope => return Err(ApiError(StatusCode::BAD_REQUEST, format!("Invalid scope: {}", scope))),
+    }
+
+    Ok(options)
+}
+
+/// Storage rejects a percent-encoded `..` or `/` too; checking up front makes it a 400
+fn validate_identifier(kind: &str, id: &str) -> std::result::Result<(), ApiError> {
+    storage::validate_identifier(kind, id).map_err(|e| ApiError(StatusCode::BAD_REQUEST, e.to_string()))
+}
+
+fn validate_table(project: &str, dataset: &str, table: &str) -> std::result::Result<(), ApiError> {
+    validate_identifier("project", project)?;
+    validate_identifier("dataset", dataset)?;
+    validate_identifier("table", table)
+}
+
+/// Validate the optional project and dataset filters of a listing
+fn validate_scope(project: Option<&str>, dataset: Option<&str>) -> std::result::Result<(), ApiError> {
+    if project.is_none() && dataset.is_some() {
+        return Err(ApiError(StatusCode::BAD_REQUEST, "Cannot specify dataset without project".to_string()));
+    }
+    project.map_or(Ok(()), |p| validate_identifier("project", p))?;
+    dataset.map_or(Ok(()), |d| validate_identifier("dataset", d))
+}
+
+/// Run catalog I/O (file system, git, the catalog lock) on the blocking thread pool
+async fn blocking<T: Send + 'static>(
+    f: impl FnOnce() -> std::result::Result<T, ApiError> + Send + 'static,
+) -> std::result::Result<T, ApiError> {
+    tokio::task::spawn_blocking(f).await
+        .map_err(|e| ApiError(StatusCode::INTERNAL_SERVER_ERROR, format!("Request handler failed: {}", e)))?
+}
+
+fn ensure_exists(storage: &dyn Storage, project: &str, dataset: &str, table: &str) -> std::result::Result<(), ApiError> {
+    validate_table(project, dataset, table)?;
+    if !storage.exists(project, dataset, table)? {
+        return Err(ApiError(
+            StatusCode::NOT_FOUND,
+            format!("Table metadata not found: {}.{}.{}", project, dataset, table),
+        ));
+    }
+    Ok(())
+}
+
+fn load_existing(storage: &dyn Storage, project: &str, dataset: &str, table: &str) -> std::result::Result<TableMetadata, ApiError> {
+    ensure_exists(storage, project, dataset, table)?;
+    Ok(storage.load(project, dataset, table)?)
+}
+
+/// Apply `edit` through [`update_table_metadata`], recording `message` in git history.
+/// Lock contention is reported as 503.
+fn edit_table(
+    project: &str,
+    dataset: &str,
+    table: &str,
+    message: &str,
+    edit: impl FnOnce(&mut TableMetadata) -> std::result::Result<(), ApiError>,
+) -> std::result::Result<TableMetadata, ApiError> {
+    let storage = open_storage()?;
+    ensure_exists(&*storage, project, dataset, table)?;
+    Ok(update_table_metadata(&*storage, project, dataset, table, message, |metadata| Ok(edit(metadata)?))?)
+}
+
+fn ensure_writable(state: &AppState) -> std::result::Result<(), ApiError> {
+    if state.writable {
+        Ok(())
+    } else {
+        Err(ApiError(StatusCode::FORBIDDEN, "Server is read-only (start with --write to enable updates)".to_string()))
+    }
+}
+
+async fn list_handler(Query(query): Query<ListQuery>) -> ApiResult<Vec<serde_json::Value>> {
+    validate_scope(query.project.as_deref(), query.dataset.as_deref())?;
+    let tables = blocking(move || Ok(open_storage()?.list(query.project.as_deref(), query.dataset.as_deref())?)).await?;
+    Ok(Json(tables.into_iter()
+        .map(|(project, dataset, table)| serde_json::json!({
+            "project": project,
+            "dataset": dataset,
+            "table": table,
+        }))
+        .collect()))
+}
+
+async fn show_handler(Path((project, dataset, table)): Path<(String, String, String)>) -> ApiResult<TableMetadata> {
+    Ok(Json(blocking(move || load_existing(&*open_storage()?, &project, &dataset, &table)).await?))
+}
+
+async fn search_handler(Query(query): Query<SearchQuery>) -> ApiResult<Vec<crate::models::SearchResult>> {
+    let options = search_options(query)?;
+    validate_scope(options.project_filter.as_deref(), options.dataset_filter.as_deref())?;
+    let outcome = blocking(move || search_tables(&options)
+        .map_err(|e| ApiError(StatusCode::BAD_REQUEST, format!("{:#}", e)))).await?;
+    Ok(Json(outcome.results))
+}
+
+async fn patch_table_handler(
+    State(state): State<AppState>,
+    Path((project, dataset, table)): Path<(String, String, String)>,
+    Json(patch): Json<TablePatch>,
+) -> ApiResult<TableMetadata> {
+    ensure_writable(&state)?;
+    validate_table(&project, &dataset, &table)?;
+    let table_path = format!("{}.{}.{}", project, dataset, table);
+    let mut changed: Vec<String> = patch.columns.keys().map(|path| format!("{}.{}", table_path, path)).collect();
+    if patch.description.is_some() {
+        changed.push(table_path.clone());
+    }
+    let message = match changed.as_slice() {
+        [target] => format!("edit: {} description", target),
+        _ => format!("edit: {} descriptions", table_path),
+    };
+
+    blocking(move || edit_table(&project, &dataset, &table, &message, |metadata| {
+        // Validate every column before changing anything
+        for path in patch.columns.keys() {
+            if metadata.find_column(path).is_none() {
+                return Err(ApiError(StatusCode::NOT_FOUND, format!("Column '{}' not found in table", path)));
+            }
+        }
+
+        if let Some(description) = patch.description {
+            metadata.table.description = normalize(description);
+        }
+        for (path, description) in patch.columns {
+            if let Some(column) = metadata.find_column_mut(&path) {
+                column.description = normalize(description);
+            }
+        }
+        Ok(())
+    }).map(Json)).await
+}
+
+async fn put_table_description_handler(
+    State(state): State<AppState>,
+    Path((project, dataset, table)): Path<(String, String, String)>,
+    Json(body): Json<DescriptionBody>,
+) -> ApiResult<TableMetadata> {
+    ensure_writable(&state)?;
+    validate_table(&project, &dataset, &table)?;
+    let message = format!("edit: {}.{}.{} description", project, dataset, table);
+    blocking(move || edit_table(&project, &dataset, &table, &message, |metadata| {
+        metadata.table.description = normalize(body.description);
+        Ok(())
+    }).map(Json)).await
+}
+
+async fn put_column_description_handler(
+    State(state): State<AppState>,
+    Path((project, dataset, table, column)): Path<(String, String, String, String)>,
+    Json(body): Json<DescriptionBody>,
+) -> ApiResult<TableMetadata> {
+    ensure_writable(&state)?;
+    validate_table(&project, &dataset, &table)?;
+    let message = format!("edit: {}.{}.{}.{} description", project, dataset, table, column);
+    blocking(move || edit_table(&project, &dataset, &table, &message, |metadata| {
+        let target = metadata.find_column_mut(&column)
+            .ok_or_else(|| ApiError(StatusCode::NOT_FOUND, format!("Column '{}' not found in table", column)))?;
+        target.description = normalize(body.description);
+        Ok(())
+    }).map(Json)).await
+}
+
+/// Routes of the REST API; write endpoints answer 403 unless `writable` is set
+pub fn router(writable: bool) -> Router {
+    Router::new()
+        .route("/tables", get(list_handler))
+        .route("/tables/{project}/{dataset}/{table}", get(show_handler).patch(patch_table_handler))
+        .route("/tables/{project}/{dataset}/{table}/description", put(put_table_description_handler))
+        .route("/tables/{project}/{dataset}/{table}/columns/{column}/description", put(put_column_description_handler))
+        .route("/search", get(search_handler))
+        .with_state(AppState { writable })
+}
+
+/// Serve the REST API until the process is stopped
+pub async fn serve(addr: SocketAddr, writable: bool) -> Result<()> {
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    println!("Serving bq-meta API on http://{} ({})", listener.local_addr()?,
+             if writable { "read-write" } else { "read-only" });
+    axum::serve(listener, router(writable)).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_search_scope() {
+        let query = |scope: &str| SearchQuery {
+            q: "user".to_string(),
+            scope: Some(scope.to_string()),
+            regex: false,
+            case_sensitive: false,
+            project: None,
+            dataset: None,
+            limit: None,
+        };
+        assert!(search_options(query("col-desc")).unwrap().search_column_desc);
+        assert!(search_options(query("bogus")).is_err());
+    }
+
+    #[test]
+    fn test_validate_table() {
+        assert!(validate_table("my-project", "analytics_v2", "events_2024").is_ok());
+        assert!(validate_table("..", "d", "t").is_err());
+        assert!(validate_table("p", "d/..", "t").is_err());
+        assert!(validate_table("p", "d", "").is_err());
+        assert!(validate_scope(Some("p"), Some("..")).is_err());
+        assert!(validate_scope(None, Some("d")).is_err());
+    }
+
+    #[test]
+    fn test_error_status() {
+        let status = |err: anyhow::Error| ApiError::from(err).0;
+        let missing = ApiError(StatusCode::NOT_FOUND, "Column 'x' not found in table".to_string());
+        assert_eq!(status(missing.into()), StatusCode::NOT_FOUND);
+        assert_eq!(status(anyhow::anyhow!("disk full")), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    #[tokio::test]
+    async fn test_read_only_rejects_writes() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, router(false)).await.unwrap() });
+
+        let response = reqwest::Client::new()
+            .put(format!("http://{}/tables/p/d/t/description", addr))
+            .json(&serde_json::json!({ "description": "new" }))
+            .send().await.unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::FORBIDDEN);
+
+        let response = reqwest::get(format!("http://{}/tables/p/d/..%2F..%2Fsecret", addr)).await.unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
+    }
+}
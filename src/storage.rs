@@ -1,122 +1,637 @@
 use anyhow::{Context, Result};
-use std::path::PathBuf;
-use std::fs;
-
-use crate::config::get_data_dir;
-use crate::models::TableMetadata;
-
-pub fn get_table_path(project_id: &str, dataset_id: &str, table_name: &str) -> Result<PathBuf> {
-    let data_dir = get_data_dir()?;
-    let table_path = data_dir
-        .join(project_id)
-        .join(dataset_id)
-        .join(format!("{}.yaml", table_name));
-    Ok(table_path)
-}
-
-pub fn load_table_metadata(project_id: &str, dataset_id: &str, table_name: &str) -> Result<TableMetadata> {
-    let table_path = get_table_path(project_id, dataset_id, table_name)?;
-    
-    if !table_path.exists() {
-        return Err(anyhow::anyhow!(
-            "Table metadata not found: {}.{}.{}",
-            project_id, dataset_id, table_name
-        ));
-    }
-
-    let content = fs::read_to_string(&table_path)
-        .with_context(|| format!("Failed to read table metadata: {}", table_path.display()))?;
-    
-    let metadata: TableMetadata = serde_yaml::from_str(&content)
-        .with_context(|| format!("Failed to parse table metadata: {}", table_path.display()))?;
-    
-    Ok(metadata)
+use rusqlite::{params, Connection, OptionalExtension};
+use std::cell::Cell;
+use std::fs::{self, File, TryLockError};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crate::config::{get_data_dir, get_data_path, load_config};
+use crate::history;
+use crate::journal;
+use crate::migration;
+use crate::models::{Config, StorageBackend, TableMetadata};
+
+/// Backend holding table metadata, keyed by project, dataset and table name
+pub trait Storage {
+    fn load(&self, project_id: &str, dataset_id: &str, table_name: &str) -> Result<TableMetadata>;
+    /// Insert or replace the table identified by `metadata.table`
+    fn save(&self, metadata: &TableMetadata) -> Result<()>;
+    /// Tables as `(project, dataset, table)`, sorted
+    fn list(&self, project_id: Option<&str>, dataset_id: Option<&str>) -> Result<Vec<(String, String, String)>>;
+    fn delete(&self, project_id: &str, dataset_id: &str, table_name: &str) -> Result<()>;
+    fn exists(&self, project_id: &str, dataset_id: &str, table_name: &str) -> Result<bool>;
+    /// `(modified, size)` of a stored table, used by the search index to skip unchanged tables
+    fn stamp(&self, project_id: &str, dataset_id: &str, table_name: &str) -> Result<(u64, u64)>;
+    /// `format_version` of a stored table, used by `migrate` to find outdated tables
+    fn format_version(&self, project_id: &str, dataset_id: &str, table_name: &str) -> Result<u32>;
+    /// Where a table is stored, for messages and diagnostics
+    fn location(&self, project_id: &str, dataset_id: &str, table_name: &str) -> Result<String>;
+    /// Start a transaction; transactions nest. Backends without transactions ignore these.
+    fn begin(&self) -> Result<()> {
+        Ok(())
+    }
+    fn commit(&self) -> Result<()> {
+        Ok(())
+    }
+    fn rollback(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Run `f` in a storage transaction, committed if it succeeds and rolled back otherwise
+pub fn transaction<T, E: From<anyhow::Error>>(
+    storage: &dyn Storage,
+    f: impl FnOnce() -> std::result::Result<T, E>,
+) -> std::result::Result<T, E> {
+    storage.begin()?;
+    let result = f().and_then(|value| Ok(storage.commit().map(|()| value)?));
+    if result.is_err() {
+        let _ = storage.rollback();
+    }
+    result
+}
+
+/// Distinguishes temporary files of concurrent writes within one process
+static TMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Write a file atomically: the content goes to a temporary file in the same directory,
+/// which is synced and then renamed over `path`, so readers never see a partial write
+pub fn write_atomic(path: &Path, content: &[u8]) -> Result<()> {
+    let dir = path.parent().unwrap_or(Path::new("."));
+    let file_name = path.file_name().map(|n| n.to_string_lossy()).unwrap_or_default();
+    let tmp_path = dir.join(format!(
+        ".{}.tmp-{}-{}",
+        file_name,
+        std::process::id(),
+        TMP_COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+
+    let result = (|| -> std::io::Result<()> {
+        let mut file = File::create(&tmp_path)?;
+        file.write_all(content)?;
+        file.sync_all()?;
+        fs::rename(&tmp_path, path)
+    })();
+    if result.is_err() {
+        let _ = fs::remove_file(&tmp_path);
+    }
+    result.with_context(|| format!("Failed to write {}", path.display()))
 }
 
-pub fn save_table_metadata(metadata: &TableMetadata) -> Result<()> {
-    let table_path = get_table_path(
-        &metadata.table.project_id,
-        &metadata.table.dataset_id,
-        &metadata.table.name,
-    )?;
-    
-    // Create parent directories if they don't exist
-    if let Some(parent) = table_path.parent() {
-        fs::create_dir_all(parent)
-            .with_context(|| format!("Failed to create directory: {}", parent.display()))?;
-    }
-
-    let content = serde_yaml::to_string(metadata)
-        .context("Failed to serialize table metadata")?;
-    
-    fs::write(&table_path, content)
-        .with_context(|| format!("Failed to write table metadata: {}", table_path.display()))?;
-    
+/// How long to wait for another process to release the catalog lock
+const LOCK_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Error from [`CatalogLock::acquire`] when another process keeps holding the lock
+#[derive(Debug)]
+pub struct CatalogBusy(PathBuf);
+
+impl std::fmt::Display for CatalogBusy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Another bq-meta process is modifying the catalog (lock held on {}); try again when it finishes",
+            self.0.display()
+        )
+    }
+}
+
+impl std::error::Error for CatalogBusy {}
+
+/// Exclusive advisory lock on the catalog, held around read-modify-write cycles and
+/// released when dropped
+pub struct CatalogLock {
+    _file: File,
+}
+
+impl CatalogLock {
+    /// Take the lock on `${BQ_META_PATH}/.lock`, waiting briefly if another process holds it
+    pub fn acquire() -> Result<Self> {
+        let data_path = get_data_path()?;
+        fs::create_dir_all(&data_path)
+            .with_context(|| format!("Failed to create directory: {}", data_path.display()))?;
+        let path = data_path.join(".lock");
+        let file = File::options().create(true).truncate(false).write(true).open(&path)
+            .with_context(|| format!("Failed to open lock file: {}", path.display()))?;
+
+        let started = Instant::now();
+        loop {
+            match file.try_lock() {
+                Ok(()) => return Ok(CatalogLock { _file: file }),
+                Err(TryLockError::WouldBlock) if started.elapsed() < LOCK_TIMEOUT => {
+                    std::thread::sleep(Duration::from_millis(50));
+                }
+                Err(TryLockError::WouldBlock) => {
+                    return Err(CatalogBusy(path).into());
+                }
+                Err(TryLockError::Error(e)) => {
+                    return Err(e).with_context(|| format!("Failed to lock {}", path.display()));
+                }
+            }
+        }
+    }
+}
+
+fn not_found(project_id: &str, dataset_id: &str, table_name: &str) -> anyhow::Error {
+    anyhow::anyhow!("Table metadata not found: {}.{}.{}", project_id, dataset_id, table_name)
+}
+
+/// Reject names that aren't BigQuery identifiers (letters, digits, `_` and `-`), so a
+/// `..` or `/` in a table spec can't reach outside the catalog
+pub fn validate_identifier(kind: &str, id: &str) -> Result<()> {
+    if id.is_empty() || !id.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '-') {
+        return Err(anyhow::anyhow!("Invalid {} name: {:?}", kind, id));
+    }
     Ok(())
 }
 
-pub fn list_tables(project_id: Option<&str>, dataset_id: Option<&str>) -> Result<Vec<(String, String, String)>> {
-    let data_dir = get_data_dir()?;
-    let mut tables = Vec::new();
-
-    if !data_dir.exists() {
-        return Ok(tables);
-    }
-
-    let search_dir = match (project_id, dataset_id) {
-        (Some(p), Some(d)) => data_dir.join(p).join(d),
-        (Some(p), None) => data_dir.join(p),
-        (None, None) => data_dir.clone(),
-        (None, Some(_)) => return Err(anyhow::anyhow!("Cannot specify dataset without project")),
-    };
-
-    if !search_dir.exists() {
-        return Ok(tables);
-    }
-
-    fn collect_yaml_files(dir: &PathBuf, base_dir: &PathBuf, tables: &mut Vec<(String, String, String)>) -> Result<()> {
-        for entry in fs::read_dir(dir)? {
-            let entry = entry?;
-            let path = entry.path();
-            
-            if path.is_dir() {
-                collect_yaml_files(&path, base_dir, tables)?;
-            } else if path.extension().map_or(false, |ext| ext == "yaml") {
-                if let Some(table_name) = path.file_stem().and_then(|s| s.to_str()) {
-                    let relative_path = path.strip_prefix(base_dir)?;
-                    let components: Vec<&str> = relative_path.components()
-                        .filter_map(|c| c.as_os_str().to_str())
-                        .collect();
-                    
-                    if components.len() >= 3 {
-                        let project = components[0].to_string();
-                        let dataset = components[1].to_string();
-                        let table = table_name.to_string();
-                        tables.push((project, dataset, table));
+/// A dataset filter is only meaningful within a project
+pub(crate) fn check_scope(project_id: Option<&str>, dataset_id: Option<&str>) -> Result<()> {
+    if project_id.is_none() && dataset_id.is_some() {
+        return Err(anyhow::anyhow!("Cannot specify dataset without project"));
+    }
+    project_id.map_or(Ok(()), |p| validate_identifier("project", p))?;
+    dataset_id.map_or(Ok(()), |d| validate_identifier("dataset", d))
+}
+
+/// Open the backend selected by `storage.backend` in config.yaml. Commands open it once
+/// and pass it along rather than reopening it per table.
+pub fn open_storage() -> Result<Box<dyn Storage>> {
+    let config = load_config()?;
+    open_backend(&config, config.storage.backend)
+}
+
+/// Open a specific backend, using the configured SQLite path
+pub fn open_backend(config: &Config, backend: StorageBackend) -> Result<Box<dyn Storage>> {
+    Ok(match backend {
+        StorageBackend::Yaml => Box::new(YamlStorage::new(get_data_dir()?)),
+        StorageBackend::Sqlite => {
+            // Relative paths are resolved against the data path
+            let path = get_data_path()?.join(config.storage.path.as_deref().unwrap_or("catalog.db"));
+            Box::new(SqliteStorage::open(&path)?)
+        }
+    })
+}
+
+/// One YAML file per table under `data/{project}/{dataset}/{table}.yaml`
+pub struct YamlStorage {
+    data_dir: PathBuf,
+}
+
+impl YamlStorage {
+    pub fn new(data_dir: PathBuf) -> Self {
+        YamlStorage { data_dir }
+    }
+
+    fn table_path(&self, project_id: &str, dataset_id: &str, table_name: &str) -> Result<PathBuf> {
+        validate_identifier("project", project_id)?;
+        validate_identifier("dataset", dataset_id)?;
+        validate_identifier("table", table_name)?;
+        Ok(self.data_dir
+            .join(project_id)
+            .join(dataset_id)
+            .join(format!("{}.yaml", table_name)))
+    }
+}
+
+impl Storage for YamlStorage {
+    fn load(&self, project_id: &str, dataset_id: &str, table_name: &str) -> Result<TableMetadata> {
+        let table_path = self.table_path(project_id, dataset_id, table_name)?;
+
+        if !table_path.exists() {
+            return Err(not_found(project_id, dataset_id, table_name));
+        }
+
+        let content = fs::read_to_string(&table_path)
+            .with_context(|| format!("Failed to read table metadata: {}", table_path.display()))?;
+
+        let metadata = migration::parse_table(&content)
+            .with_context(|| format!("Failed to parse table metadata: {}", table_path.display()))?;
+
+        Ok(metadata)
+    }
+
+    fn save(&self, metadata: &TableMetadata) -> Result<()> {
+        let table_path = self.table_path(
+            &metadata.table.project_id,
+            &metadata.table.dataset_id,
+            &metadata.table.name,
+        )?;
+
+        // Create parent directories if they don't exist
+        if let Some(parent) = table_path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create directory: {}", parent.display()))?;
+        }
+
+        let content = migration::table_to_string(metadata)
+            .context("Failed to serialize table metadata")?;
+
+        write_atomic(&table_path, content.as_bytes())
+            .context("Failed to write table metadata")?;
+
+        Ok(())
+    }
+
+    fn list(&self, project_id: Option<&str>, dataset_id: Option<&str>) -> Result<Vec<(String, String, String)>> {
+        check_scope(project_id, dataset_id)?;
+        let data_dir = &self.data_dir;
+        let mut tables = Vec::new();
+
+        if !data_dir.exists() {
+            return Ok(tables);
+        }
+
+        let search_dir = match (project_id, dataset_id) {
+            (Some(p), Some(d)) => data_dir.join(p).join(d),
+            (Some(p), None) => data_dir.join(p),
+            _ => data_dir.clone(),
+        };
+
+        if !search_dir.exists() {
+            return Ok(tables);
+        }
+
+        fn collect_yaml_files(dir: &PathBuf, base_dir: &PathBuf, tables: &mut Vec<(String, String, String)>) -> Result<()> {
+            for entry in fs::read_dir(dir)? {
+                let entry = entry?;
+                let path = entry.path();
+
+                if path.is_dir() {
+                    collect_yaml_files(&path, base_dir, tables)?;
+                } else if path.extension().is_some_and(|ext| ext == "yaml") {
+                    if let Some(table_name) = path.file_stem().and_then(|s| s.to_str()) {
+                        let relative_path = path.strip_prefix(base_dir)?;
+                        let components: Vec<&str> = relative_path.components()
+                            .filter_map(|c| c.as_os_str().to_str())
+                            .collect();
+
+                        if components.len() >= 3 {
+                            let project = components[0].to_string();
+                            let dataset = components[1].to_string();
+                            let table = table_name.to_string();
+                            tables.push((project, dataset, table));
+                        }
                     }
                 }
             }
+            Ok(())
         }
+
+        collect_yaml_files(&search_dir, data_dir, &mut tables)?;
+        tables.sort();
+        Ok(tables)
+    }
+
+    fn delete(&self, project_id: &str, dataset_id: &str, table_name: &str) -> Result<()> {
+        let table_path = self.table_path(project_id, dataset_id, table_name)?;
+
+        if !table_path.exists() {
+            return Err(not_found(project_id, dataset_id, table_name));
+        }
+
+        fs::remove_file(&table_path)
+            .with_context(|| format!("Failed to delete table metadata: {}", table_path.display()))?;
+
         Ok(())
     }
 
-    collect_yaml_files(&search_dir, &data_dir, &mut tables)?;
-    tables.sort();
-    Ok(tables)
+    fn exists(&self, project_id: &str, dataset_id: &str, table_name: &str) -> Result<bool> {
+        Ok(self.table_path(project_id, dataset_id, table_name)?.exists())
+    }
+
+    fn stamp(&self, project_id: &str, dataset_id: &str, table_name: &str) -> Result<(u64, u64)> {
+        let path = self.table_path(project_id, dataset_id, table_name)?;
+        let meta = fs::metadata(&path)
+            .with_context(|| format!("Failed to read file metadata: {}", path.display()))?;
+        let modified = meta.modified()?
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_nanos() as u64);
+        Ok((modified, meta.len()))
+    }
+
+    fn format_version(&self, project_id: &str, dataset_id: &str, table_name: &str) -> Result<u32> {
+        let path = self.table_path(project_id, dataset_id, table_name)?;
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read table metadata: {}", path.display()))?;
+        migration::document_version(&content)
+            .with_context(|| format!("Failed to parse table metadata: {}", path.display()))
+    }
+
+    fn location(&self, project_id: &str, dataset_id: &str, table_name: &str) -> Result<String> {
+        Ok(self.table_path(project_id, dataset_id, table_name)?.display().to_string())
+    }
+}
+
+/// All tables in a single SQLite file, one row per table holding its YAML document.
+///
+/// Each write is a single statement, and read-modify-write cycles run in a transaction, so
+/// concurrent writers wait on the database lock instead of clobbering each other.
+pub struct SqliteStorage {
+    conn: Connection,
+    path: PathBuf,
+    /// Open transactions; nested ones are savepoints
+    depth: Cell<usize>,
 }
 
-pub fn delete_table_metadata(project_id: &str, dataset_id: &str, table_name: &str) -> Result<()> {
-    let table_path = get_table_path(project_id, dataset_id, table_name)?;
-    
-    if !table_path.exists() {
-        return Err(anyhow::anyhow!(
-            "Table metadata not found: {}.{}.{}",
-            project_id, dataset_id, table_name
-        ));
+impl SqliteStorage {
+    pub fn open(path: &Path) -> Result<Self> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create directory: {}", parent.display()))?;
+        }
+        let conn = Connection::open(path)
+            .with_context(|| format!("Failed to open SQLite catalog: {}", path.display()))?;
+        // Rollback journal rather than WAL, which doesn't work on network file systems
+        conn.busy_timeout(Duration::from_secs(10))?;
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS tables (
+                project_id TEXT NOT NULL,
+                dataset_id TEXT NOT NULL,
+                table_name TEXT NOT NULL,
+                metadata TEXT NOT NULL,
+                modified INTEGER NOT NULL,
+                PRIMARY KEY (project_id, dataset_id, table_name)
+            )",
+        ).with_context(|| format!("Failed to initialize SQLite catalog: {}", path.display()))?;
+        Ok(SqliteStorage { conn, path: path.to_path_buf(), depth: Cell::new(0) })
     }
 
-    fs::remove_file(&table_path)
-        .with_context(|| format!("Failed to delete table metadata: {}", table_path.display()))?;
-    
-    Ok(())
-}
\ No newline at end of file
+    /// The stored YAML document of a table
+    fn document(&self, project_id: &str, dataset_id: &str, table_name: &str) -> Result<String> {
+        self.conn
+            .query_row(
+                "SELECT metadata FROM tables WHERE project_id = ?1 AND dataset_id = ?2 AND table_name = ?3",
+                params![project_id, dataset_id, table_name],
+                |row| row.get(0),
+            )
+            .optional()?
+            .ok_or_else(|| not_found(project_id, dataset_id, table_name))
+    }
+}
+
+impl Storage for SqliteStorage {
+    fn load(&self, project_id: &str, dataset_id: &str, table_name: &str) -> Result<TableMetadata> {
+        let content = self.document(project_id, dataset_id, table_name)?;
+        migration::parse_table(&content).with_context(|| {
+            format!("Failed to parse table metadata: {}", self.location(project_id, dataset_id, table_name).unwrap_or_default())
+        })
+    }
+
+    fn save(&self, metadata: &TableMetadata) -> Result<()> {
+        let content = migration::table_to_string(metadata)
+            .context("Failed to serialize table metadata")?;
+        let modified = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_nanos() as i64);
+
+        self.conn.execute(
+            "INSERT INTO tables (project_id, dataset_id, table_name, metadata, modified)
+             VALUES (?1, ?2, ?3, ?4, ?5)
+             ON CONFLICT (project_id, dataset_id, table_name)
+             DO UPDATE SET metadata = excluded.metadata, modified = excluded.modified",
+            params![metadata.table.project_id, metadata.table.dataset_id, metadata.table.name, content, modified],
+        ).with_context(|| format!("Failed to write table metadata to {}", self.path.display()))?;
+        Ok(())
+    }
+
+    fn list(&self, project_id: Option<&str>, dataset_id: Option<&str>) -> Result<Vec<(String, String, String)>> {
+        check_scope(project_id, dataset_id)?;
+        let mut stmt = self.conn.prepare(
+            "SELECT project_id, dataset_id, table_name FROM tables
+             WHERE (?1 IS NULL OR project_id = ?1) AND (?2 IS NULL OR dataset_id = ?2)
+             ORDER BY project_id, dataset_id, table_name",
+        )?;
+        let tables = stmt
+            .query_map(params![project_id, dataset_id], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(tables)
+    }
+
+    fn delete(&self, project_id: &str, dataset_id: &str, table_name: &str) -> Result<()> {
+        let deleted = self.conn.execute(
+            "DELETE FROM tables WHERE project_id = ?1 AND dataset_id = ?2 AND table_name = ?3",
+            params![project_id, dataset_id, table_name],
+        )?;
+        if deleted == 0 {
+            return Err(not_found(project_id, dataset_id, table_name));
+        }
+        Ok(())
+    }
+
+    fn exists(&self, project_id: &str, dataset_id: &str, table_name: &str) -> Result<bool> {
+        Ok(self.conn
+            .query_row(
+                "SELECT 1 FROM tables WHERE project_id = ?1 AND dataset_id = ?2 AND table_name = ?3",
+                params![project_id, dataset_id, table_name],
+                |_| Ok(()),
+            )
+            .optional()?
+            .is_some())
+    }
+
+    fn stamp(&self, project_id: &str, dataset_id: &str, table_name: &str) -> Result<(u64, u64)> {
+        let (modified, size): (i64, i64) = self.conn
+            .query_row(
+                "SELECT modified, length(metadata) FROM tables WHERE project_id = ?1 AND dataset_id = ?2 AND table_name = ?3",
+                params![project_id, dataset_id, table_name],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()?
+            .ok_or_else(|| not_found(project_id, dataset_id, table_name))?;
+        Ok((modified as u64, size as u64))
+    }
+
+    fn format_version(&self, project_id: &str, dataset_id: &str, table_name: &str) -> Result<u32> {
+        let content = self.document(project_id, dataset_id, table_name)?;
+        migration::document_version(&content).with_context(|| {
+            format!("Failed to parse table metadata: {}", self.location(project_id, dataset_id, table_name).unwrap_or_default())
+        })
+    }
+
+    fn location(&self, project_id: &str, dataset_id: &str, table_name: &str) -> Result<String> {
+        Ok(format!("{}#{}.{}.{}", self.path.display(), project_id, dataset_id, table_name))
+    }
+
+    fn begin(&self) -> Result<()> {
+        // IMMEDIATE takes the write lock up front, so two read-modify-writes can't both read
+        // and then fail to upgrade
+        let sql = if self.depth.get() == 0 { "BEGIN IMMEDIATE" } else { "SAVEPOINT nested" };
+        self.conn.execute_batch(sql)
+            .with_context(|| format!("Failed to start a transaction on {}", self.path.display()))?;
+        self.depth.set(self.depth.get() + 1);
+        Ok(())
+    }
+
+    fn commit(&self) -> Result<()> {
+        let sql = if self.depth.get() == 1 { "COMMIT" } else { "RELEASE nested" };
+        self.conn.execute_batch(sql)
+            .with_context(|| format!("Failed to commit to {}", self.path.display()))?;
+        self.depth.set(self.depth.get().saturating_sub(1));
+        Ok(())
+    }
+
+    fn rollback(&self) -> Result<()> {
+        let sql = if self.depth.get() == 1 { "ROLLBACK" } else { "ROLLBACK TO nested; RELEASE nested" };
+        self.depth.set(self.depth.get().saturating_sub(1));
+        self.conn.execute_batch(sql)
+            .with_context(|| format!("Failed to roll back {}", self.path.display()))?;
+        Ok(())
+    }
+}
+
+/// Copy every table from one backend to another, returning the number copied
+pub fn copy_tables(from: &dyn Storage, to: &dyn Storage) -> Result<usize> {
+    let tables = from.list(None, None)?;
+    for (project_id, dataset_id, table_name) in &tables {
+        to.save(&from.load(project_id, dataset_id, table_name)?)?;
+    }
+    Ok(tables.len())
+}
+
+/// Save a table, recording its previous state in the undo journal. Callers hold the catalog
+/// lock, which also keeps journal entries from interleaving.
+pub fn save_table_metadata(storage: &dyn Storage, metadata: &TableMetadata) -> Result<()> {
+    let table = &metadata.table;
+    transaction(storage, || {
+        let (before, unreadable) = if storage.exists(&table.project_id, &table.dataset_id, &table.name)? {
+            // An unreadable file can still be overwritten (it just can't be restored), but one
+            // from a newer bq-meta must not be downgraded
+            match storage.load(&table.project_id, &table.dataset_id, &table.name) {
+                Ok(metadata) => (Some(metadata), false),
+                Err(err) if migration::is_newer_format(&err) => return Err(err),
+                Err(_) => (None, true),
+            }
+        } else {
+            (None, false)
+        };
+        storage.save(metadata)?;
+        journal::record(&table.project_id, &table.dataset_id, &table.name, before, unreadable, Some(metadata))
+    })
+}
+
+/// Load a table, apply `update`, save it and record `message` in git history while holding
+/// the catalog lock, so concurrent edits don't overwrite each other. Nothing is saved if
+/// `update` fails.
+pub fn update_table_metadata(
+    storage: &dyn Storage,
+    project_id: &str,
+    dataset_id: &str,
+    table_name: &str,
+    message: &str,
+    update: impl FnOnce(&mut TableMetadata) -> Result<()>,
+) -> Result<TableMetadata> {
+    let _lock = CatalogLock::acquire()?;
+    let metadata = transaction(storage, || -> Result<TableMetadata> {
+        let mut metadata = storage.load(project_id, dataset_id, table_name)?;
+        let before = metadata.clone();
+        update(&mut metadata)?;
+        storage.save(&metadata)?;
+        journal::record(project_id, dataset_id, table_name, Some(before), false, Some(&metadata))?;
+        Ok(metadata)
+    })?;
+    history::record(message, &[(project_id.to_string(), dataset_id.to_string(), table_name.to_string())])?;
+    Ok(metadata)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{ColumnInfo, TableInfo};
+
+    fn roundtrip(storage: &dyn Storage) {
+        let mut metadata = TableMetadata {
+            table: TableInfo {
+                name: "events".to_string(),
+                project_id: "p".to_string(),
+                dataset_id: "d".to_string(),
+                ..Default::default()
+            },
+            columns: vec![ColumnInfo { name: "id".to_string(), column_type: "STRING".to_string(), ..Default::default() }],
+        };
+        storage.save(&metadata).unwrap();
+        metadata.table.name = "users".to_string();
+        metadata.table.dataset_id = "e".to_string();
+        storage.save(&metadata).unwrap();
+        metadata.table.description = Some("Users".to_string());
+        storage.save(&metadata).unwrap();
+
+        let all = storage.list(None, None).unwrap();
+        assert_eq!(all, vec![
+            ("p".to_string(), "d".to_string(), "events".to_string()),
+            ("p".to_string(), "e".to_string(), "users".to_string()),
+        ]);
+        assert_eq!(storage.list(Some("p"), Some("e")).unwrap().len(), 1);
+        assert!(storage.list(None, Some("e")).is_err());
+
+        assert_eq!(storage.load("p", "e", "users").unwrap().table.description.as_deref(), Some("Users"));
+        assert!(storage.exists("p", "d", "events").unwrap());
+        assert!(storage.stamp("p", "d", "events").unwrap().1 > 0);
+
+        storage.delete("p", "d", "events").unwrap();
+        assert!(!storage.exists("p", "d", "events").unwrap());
+        assert!(storage.load("p", "d", "events").is_err());
+        assert!(storage.delete("p", "d", "events").is_err());
+    }
+
+    #[test]
+    fn test_backends() {
+        let dir = std::env::temp_dir().join(format!("bq-meta-storage-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+
+        roundtrip(&YamlStorage::new(dir.join("data")));
+        roundtrip(&SqliteStorage::open(&dir.join("catalog.db")).unwrap());
+
+        // Copying between backends preserves every table
+        let sqlite = SqliteStorage::open(&dir.join("copy.db")).unwrap();
+        assert_eq!(copy_tables(&YamlStorage::new(dir.join("data")), &sqlite).unwrap(), 1);
+        assert!(sqlite.exists("p", "e", "users").unwrap());
+
+        // A failed transaction leaves nothing behind, including its nested transactions
+        let result: Result<()> = transaction(&sqlite, || {
+            sqlite.delete("p", "e", "users")?;
+            transaction(&sqlite, || sqlite.delete("p", "e", "users"))
+        });
+        assert!(result.is_err());
+        assert!(sqlite.exists("p", "e", "users").unwrap());
+        transaction(&sqlite, || sqlite.delete("p", "e", "users")).unwrap();
+        assert!(!sqlite.exists("p", "e", "users").unwrap());
+
+        // Names that would resolve outside the data directory are refused
+        let yaml = YamlStorage::new(dir.join("data"));
+        assert!(yaml.load("..", "..", "secret").is_err());
+        assert!(yaml.exists("p", "d", "../../../etc/passwd").is_err());
+        assert!(yaml.list(Some("p"), Some("..")).is_err());
+        assert!(validate_identifier("table", "events_2024").is_ok());
+        assert!(validate_identifier("table", "").is_err());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_write_atomic() {
+        let dir = std::env::temp_dir().join(format!("bq-meta-atomic-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("table.yaml");
+
+        write_atomic(&path, b"first").unwrap();
+        write_atomic(&path, b"second").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "second");
+
+        // Concurrent writers in one process don't share a temporary file
+        std::thread::scope(|scope| {
+            for _ in 0..8 {
+                scope.spawn(|| {
+                    for _ in 0..20 {
+                        write_atomic(&path, b"second").unwrap();
+                    }
+                });
+            }
+        });
+        assert_eq!(fs::read_to_string(&path).unwrap(), "second");
+        // No temporary files are left behind
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+
+        assert!(write_atomic(&dir.join("missing").join("table.yaml"), b"x").is_err());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,134 @@
+use serde::Serialize;
+
+use crate::models::{ColumnInfo, TableInfo, TableMetadata};
+
+/// What `pull` changed in one table
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SyncSummary {
+    /// Columns that exist remotely but were missing locally
+    pub added: Vec<String>,
+    /// Type or mode changes, as `path: OLD -> NEW`
+    pub updated: Vec<String>,
+    /// Columns that no longer exist remotely and were flagged as removed
+    pub removed: Vec<String>,
+    /// Empty local descriptions filled in from BigQuery
+    pub descriptions_filled: Vec<String>,
+}
+
+impl SyncSummary {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.updated.is_empty()
+            && self.removed.is_empty()
+            && self.descriptions_filled.is_empty()
+    }
+}
+
+/// Refresh local metadata from the remote table without losing local descriptions.
+///
+/// New columns are appended, types and modes follow the remote, columns missing remotely
+/// are flagged as removed, and descriptions are taken from the remote only where the local
+/// one is empty. Remote table properties (labels, partitioning, ...) replace local ones.
+pub fn sync_table(local: &mut TableMetadata, remote: TableMetadata) -> SyncSummary {
+    let mut summary = SyncSummary::default();
+
+    let local_description = local.table.description.take();
+    local.table = TableInfo { description: local_description, ..remote.table.clone() };
+    if local.table.description.is_none() && remote.table.description.is_some() {
+        local.table.description = remote.table.description;
+        summary.descriptions_filled.push("(table)".to_string());
+    }
+
+    sync_columns(None, &mut local.columns, remote.columns, &mut summary);
+    summary
+}
+
+fn sync_columns(prefix: Option<&str>, local: &mut Vec<ColumnInfo>, remote: Vec<ColumnInfo>, summary: &mut SyncSummary) {
+    let path_of = |name: &str| match prefix {
+        Some(p) => format!("{}.{}", p, name),
+        None => name.to_string(),
+    };
+
+    for column in local.iter_mut() {
+        if !column.removed && !remote.iter().any(|r| r.name == column.name) {
+            column.removed = true;
+            summary.removed.push(path_of(&column.name));
+        }
+    }
+
+    for remote_column in remote {
+        let path = path_of(&remote_column.name);
+        let Some(column) = local.iter_mut().find(|c| c.name == remote_column.name) else {
+            summary.added.push(path);
+            local.push(remote_column);
+            continue;
+        };
+
+        column.removed = false;
+        if column.column_type != remote_column.column_type {
+            summary.updated.push(format!("{}: {} -> {}", path, column.column_type, remote_column.column_type));
+            column.column_type = remote_column.column_type;
+        }
+        // A missing mode means NULLABLE in BigQuery
+        let local_mode = column.mode.as_deref().unwrap_or("NULLABLE");
+        let remote_mode = remote_column.mode.as_deref().unwrap_or("NULLABLE");
+        if local_mode != remote_mode {
+            summary.updated.push(format!("{}: {} -> {}", path, local_mode, remote_mode));
+            column.mode = remote_column.mode;
+        }
+        if column.description.is_none() && remote_column.description.is_some() {
+            column.description = remote_column.description;
+            summary.descriptions_filled.push(path.clone());
+        }
+        column.policy_tags = remote_column.policy_tags;
+
+        sync_columns(Some(&path), &mut column.fields, remote_column.fields, summary);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sync_table() {
+        let mut local: TableMetadata = serde_yaml::from_str(r#"
+table: {name: t, project_id: p, dataset_id: d, description: Local table}
+columns:
+  - {name: id, type: INT64, description: Local id}
+  - {name: gone, type: STRING, description: Old column}
+  - {name: empty, type: STRING}
+  - {name: nullable, type: STRING}
+  - name: rec
+    type: RECORD
+    fields: [{name: x, type: STRING}]
+"#).unwrap();
+        let remote: TableMetadata = serde_yaml::from_str(r#"
+table: {name: t, project_id: p, dataset_id: d, description: Remote table, labels: {env: prod}}
+columns:
+  - {name: id, type: STRING, mode: REQUIRED, description: Remote id}
+  - {name: empty, type: STRING, description: Filled}
+  - {name: nullable, type: STRING, mode: NULLABLE}
+  - name: rec
+    type: RECORD
+    fields: [{name: x, type: STRING}, {name: y, type: INT64}]
+  - {name: added, type: DATE}
+"#).unwrap();
+
+        let summary = sync_table(&mut local, remote.clone());
+        assert_eq!(summary.added, vec!["rec.y", "added"]);
+        assert_eq!(summary.updated, vec!["id: INT64 -> STRING", "id: NULLABLE -> REQUIRED"]);
+        assert_eq!(summary.removed, vec!["gone"]);
+        assert_eq!(summary.descriptions_filled, vec!["empty"]);
+
+        assert_eq!(local.table.description.as_deref(), Some("Local table"));
+        assert_eq!(local.table.labels["env"], "prod");
+        assert_eq!(local.columns[0].description.as_deref(), Some("Local id"));
+        assert!(local.find_column("gone").unwrap().removed);
+        assert_eq!(local.columns.last().unwrap().name, "added");
+
+        // Pulling the same schema again changes nothing
+        assert!(sync_table(&mut local, remote).is_empty());
+        assert!(local.find_column("gone").unwrap().removed);
+    }
+}
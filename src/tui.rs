@@ -0,0 +1,561 @@
+use anyhow::{Context, Result};
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout, Position, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap};
+use ratatui::{DefaultTerminal, Frame};
+
+use crate::models::TableMetadata;
+use crate::storage::{open_storage, update_table_metadata};
+
+const HELP: &str = "↑↓/jk move  ←→/hl/Tab switch pane  / filter  e edit description  q quit";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Pane {
+    Datasets,
+    Tables,
+    Columns,
+}
+
+impl Pane {
+    const ALL: [Pane; 3] = [Pane::Datasets, Pane::Tables, Pane::Columns];
+
+    fn index(self) -> usize {
+        self as usize
+    }
+
+    fn title(self) -> &'static str {
+        match self {
+            Pane::Datasets => "Datasets",
+            Pane::Tables => "Tables",
+            Pane::Columns => "Columns",
+        }
+    }
+}
+
+enum Mode {
+    Browse,
+    /// Typing into the focused pane's filter
+    Filter,
+    /// Editing a description; `column` is `None` for the table description
+    Edit { column: Option<String>, buffer: Vec<char>, cursor: usize },
+}
+
+struct TableEntry {
+    project_id: String,
+    dataset_id: String,
+    table_name: String,
+    metadata: TableMetadata,
+}
+
+/// Subsequence match of `query` in `text`, ignoring case. Consecutive characters and
+/// characters at the start of a word score higher; `None` when `query` doesn't match.
+fn fuzzy_score(text: &str, query: &str) -> Option<i64> {
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+    let mut score = 0;
+    let mut from = 0;
+    let mut previous: Option<usize> = None;
+    for q in query.to_lowercase().chars() {
+        let pos = from + text[from..].iter().position(|&c| c == q)?;
+        score += 1;
+        if previous.is_some_and(|p| p + 1 == pos) {
+            score += 5;
+        }
+        if pos == 0 || !text[pos - 1].is_alphanumeric() {
+            score += 3;
+        }
+        previous = Some(pos);
+        from = pos + 1;
+    }
+    Some(score)
+}
+
+/// Indices of the items matching `query`, best first; all items in order when it's empty
+fn filter_items<'a>(items: impl Iterator<Item = (usize, &'a str)>, query: &str) -> Vec<usize> {
+    let mut scored: Vec<(usize, i64)> = items
+        .filter_map(|(i, text)| fuzzy_score(text, query).map(|score| (i, score)))
+        .collect();
+    scored.sort_by_key(|&(_, score)| std::cmp::Reverse(score));
+    scored.into_iter().map(|(i, _)| i).collect()
+}
+
+pub struct App {
+    datasets: Vec<(String, String)>,
+    tables: Vec<TableEntry>,
+    focus: Pane,
+    filters: [String; 3],
+    /// Selected position within each pane's filtered list
+    selected: [usize; 3],
+    mode: Mode,
+    status: String,
+    /// Tables left out because they couldn't be loaded
+    skipped: usize,
+    quit: bool,
+}
+
+impl App {
+    fn new(tables: Vec<TableEntry>) -> Self {
+        let mut datasets: Vec<(String, String)> = tables.iter()
+            .map(|t| (t.project_id.clone(), t.dataset_id.clone()))
+            .collect();
+        datasets.dedup();
+
+        App {
+            datasets,
+            tables,
+            focus: Pane::Datasets,
+            filters: Default::default(),
+            selected: [0; 3],
+            mode: Mode::Browse,
+            status: String::new(),
+            skipped: 0,
+            quit: false,
+        }
+    }
+
+    /// Load every table in the catalog, skipping (and counting) tables that can't be parsed
+    pub fn load() -> Result<Self> {
+        let mut tables = Vec::new();
+        let mut skipped = 0;
+        let storage = open_storage()?;
+        for (project_id, dataset_id, table_name) in storage.list(None, None)? {
+            match storage.load(&project_id, &dataset_id, &table_name) {
+                Ok(metadata) => tables.push(TableEntry { project_id, dataset_id, table_name, metadata }),
+                Err(_) => skipped += 1,
+            }
+        }
+        let mut app = App::new(tables);
+        app.skipped = skipped;
+        Ok(app)
+    }
+
+    fn visible_datasets(&self) -> Vec<usize> {
+        let names: Vec<String> = self.datasets.iter().map(|(p, d)| format!("{}.{}", p, d)).collect();
+        filter_items(names.iter().map(String::as_str).enumerate(), &self.filters[Pane::Datasets.index()])
+    }
+
+    fn current_dataset(&self) -> Option<&(String, String)> {
+        let visible = self.visible_datasets();
+        visible.get(self.selected[Pane::Datasets.index()]).map(|&i| &self.datasets[i])
+    }
+
+    fn visible_tables(&self) -> Vec<usize> {
+        let Some((project_id, dataset_id)) = self.current_dataset() else { return Vec::new() };
+        let items = self.tables.iter().enumerate()
+            .filter(|(_, t)| &t.project_id == project_id && &t.dataset_id == dataset_id)
+            .map(|(i, t)| (i, t.table_name.as_str()));
+        filter_items(items, &self.filters[Pane::Tables.index()])
+    }
+
+    fn current_table(&self) -> Option<usize> {
+        self.visible_tables().get(self.selected[Pane::Tables.index()]).copied()
+    }
+
+    /// Dotted paths of the current table's columns matching the filter
+    fn visible_columns(&self) -> Vec<String> {
+        let Some(table) = self.current_table() else { return Vec::new() };
+        let paths: Vec<String> = self.tables[table].metadata.flatten_columns().into_iter()
+            .map(|(path, _)| path)
+            .collect();
+        filter_items(paths.iter().map(String::as_str).enumerate(), &self.filters[Pane::Columns.index()])
+            .into_iter()
+            .map(|i| paths[i].clone())
+            .collect()
+    }
+
+    fn current_column(&self) -> Option<String> {
+        self.visible_columns().into_iter().nth(self.selected[Pane::Columns.index()])
+    }
+
+    fn visible_len(&self, pane: Pane) -> usize {
+        match pane {
+            Pane::Datasets => self.visible_datasets().len(),
+            Pane::Tables => self.visible_tables().len(),
+            Pane::Columns => self.visible_columns().len(),
+        }
+    }
+
+    /// Reset the selection of every pane to the right of `pane`
+    fn reset_after(&mut self, pane: Pane) {
+        for later in Pane::ALL.iter().filter(|p| p.index() > pane.index()) {
+            self.selected[later.index()] = 0;
+        }
+    }
+
+    fn move_selection(&mut self, delta: isize) {
+        let len = self.visible_len(self.focus);
+        let current = &mut self.selected[self.focus.index()];
+        let next = current.saturating_add_signed(delta).min(len.saturating_sub(1));
+        if next != *current {
+            *current = next;
+            self.reset_after(self.focus);
+        }
+    }
+
+    fn focus_next(&mut self, forward: bool) {
+        let i = self.focus.index();
+        self.focus = Pane::ALL[if forward { (i + 1).min(2) } else { i.saturating_sub(1) }];
+    }
+
+    fn start_edit(&mut self) {
+        let Some(table) = self.current_table().filter(|_| self.focus != Pane::Datasets) else {
+            self.status = "Select a table or column to edit".to_string();
+            return;
+        };
+        let metadata = &self.tables[table].metadata;
+        let (column, description) = match self.focus {
+            Pane::Columns => {
+                let Some(path) = self.current_column() else { return };
+                let description = metadata.find_column(&path).and_then(|c| c.description.clone());
+                (Some(path), description)
+            }
+            _ => (None, metadata.table.description.clone()),
+        };
+        let buffer: Vec<char> = description.unwrap_or_default().chars().collect();
+        self.mode = Mode::Edit { column, cursor: buffer.len(), buffer };
+    }
+
+    /// Write an edited description through to storage, re-reading the table under the
+    /// catalog lock so changes made elsewhere since loading are kept
+    fn save_description(&mut self, column: Option<String>, description: String) -> Result<String> {
+        let table = self.current_table().context("No table selected")?;
+        let entry = &mut self.tables[table];
+        let description = Some(description.trim().to_string()).filter(|d| !d.is_empty());
+        let table_path = format!("{}.{}.{}", entry.project_id, entry.dataset_id, entry.table_name);
+        let target = match column {
+            Some(ref path) => format!("{}.{}", table_path, path),
+            None => table_path,
+        };
+
+        let message = format!("edit: {} description", target);
+        entry.metadata = update_table_metadata(&*open_storage()?, &entry.project_id, &entry.dataset_id, &entry.table_name, &message, |metadata| {
+            match column {
+                Some(ref path) => {
+                    metadata.find_column_mut(path)
+                        .with_context(|| format!("Column '{}' not found in table", path))?
+                        .description = description;
+                }
+                None => metadata.table.description = description,
+            }
+            Ok(())
+        })?;
+        Ok(target)
+    }
+
+    fn handle_key(&mut self, key: KeyEvent) {
+        if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+            self.quit = true;
+            return;
+        }
+
+        match &mut self.mode {
+            Mode::Browse => self.handle_browse_key(key),
+            Mode::Filter => {
+                let focus = self.focus;
+                let filter = &mut self.filters[focus.index()];
+                match key.code {
+                    KeyCode::Enter => self.mode = Mode::Browse,
+                    KeyCode::Esc => {
+                        filter.clear();
+                        self.mode = Mode::Browse;
+                    }
+                    KeyCode::Backspace => {
+                        filter.pop();
+                    }
+                    KeyCode::Char(c) => filter.push(c),
+                    _ => return,
+                }
+                self.selected[focus.index()] = 0;
+                self.reset_after(focus);
+            }
+            Mode::Edit { column, buffer, cursor } => match key.code {
+                KeyCode::Enter => {
+                    let (column, description) = (column.take(), buffer.iter().collect());
+                    self.mode = Mode::Browse;
+                    self.status = match self.save_description(column, description) {
+                        Ok(target) => format!("Saved description of {}", target),
+                        Err(e) => format!("Error: {:#}", e),
+                    };
+                }
+                KeyCode::Esc => {
+                    self.mode = Mode::Browse;
+                    self.status = "Edit cancelled".to_string();
+                }
+                KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    buffer.clear();
+                    *cursor = 0;
+                }
+                KeyCode::Char(c) => {
+                    buffer.insert(*cursor, c);
+                    *cursor += 1;
+                }
+                KeyCode::Backspace if *cursor > 0 => {
+                    *cursor -= 1;
+                    buffer.remove(*cursor);
+                }
+                KeyCode::Delete if *cursor < buffer.len() => {
+                    buffer.remove(*cursor);
+                }
+                KeyCode::Left => *cursor = cursor.saturating_sub(1),
+                KeyCode::Right => *cursor = (*cursor + 1).min(buffer.len()),
+                KeyCode::Home => *cursor = 0,
+                KeyCode::End => *cursor = buffer.len(),
+                _ => {}
+            },
+        }
+    }
+
+    fn handle_browse_key(&mut self, key: KeyEvent) {
+        self.status.clear();
+        match key.code {
+            KeyCode::Char('q') => self.quit = true,
+            KeyCode::Esc => self.filters[self.focus.index()].clear(),
+            KeyCode::Down | KeyCode::Char('j') => self.move_selection(1),
+            KeyCode::Up | KeyCode::Char('k') => self.move_selection(-1),
+            KeyCode::PageDown => self.move_selection(10),
+            KeyCode::PageUp => self.move_selection(-10),
+            KeyCode::Right | KeyCode::Char('l') | KeyCode::Tab | KeyCode::Enter => self.focus_next(true),
+            KeyCode::Left | KeyCode::Char('h') | KeyCode::BackTab => self.focus_next(false),
+            KeyCode::Char('/') => self.mode = Mode::Filter,
+            KeyCode::Char('e') => self.start_edit(),
+            _ => {}
+        }
+    }
+
+    fn run(&mut self, terminal: &mut DefaultTerminal) -> Result<()> {
+        while !self.quit {
+            terminal.draw(|frame| self.draw(frame))?;
+            if let Event::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Press {
+                    self.handle_key(key);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn draw(&self, frame: &mut Frame) {
+        let [main, detail, status] = Layout::vertical([
+            Constraint::Min(5),
+            Constraint::Length(8),
+            Constraint::Length(1),
+        ]).areas(frame.area());
+        let [left, middle, right] = Layout::horizontal([
+            Constraint::Percentage(25),
+            Constraint::Percentage(30),
+            Constraint::Percentage(45),
+        ]).areas(main);
+
+        let datasets: Vec<ListItem> = self.visible_datasets().into_iter()
+            .map(|i| ListItem::new(format!("{}.{}", self.datasets[i].0, self.datasets[i].1)))
+            .collect();
+        self.draw_list(frame, left, Pane::Datasets, datasets);
+
+        let tables: Vec<ListItem> = self.visible_tables().into_iter()
+            .map(|i| {
+                let table = &self.tables[i];
+                let style = undocumented_style(table.metadata.table.description.is_none());
+                ListItem::new(Span::styled(table.table_name.clone(), style))
+            })
+            .collect();
+        self.draw_list(frame, middle, Pane::Tables, tables);
+
+        let columns: Vec<ListItem> = match self.current_table() {
+            Some(table) => self.visible_columns().into_iter()
+                .filter_map(|path| {
+                    let column = self.tables[table].metadata.find_column(&path)?;
+                    Some(ListItem::new(Line::from(vec![
+                        Span::styled(path.clone(), undocumented_style(column.description.is_none())),
+                        Span::styled(format!(" {}", column.column_type), Style::default().fg(Color::DarkGray)),
+                    ])))
+                })
+                .collect(),
+            None => Vec::new(),
+        };
+        self.draw_list(frame, right, Pane::Columns, columns);
+
+        self.draw_detail(frame, detail);
+
+        let status_line = match &self.mode {
+            Mode::Filter => {
+                let filter = &self.filters[self.focus.index()];
+                frame.set_cursor_position(Position::new(status.x + 1 + filter.chars().count() as u16, status.y));
+                format!("/{}", filter)
+            }
+            _ if !self.status.is_empty() => self.status.clone(),
+            _ if self.skipped > 0 => format!("{}  ({} unreadable table(s) skipped)", HELP, self.skipped),
+            _ => HELP.to_string(),
+        };
+        frame.render_widget(Paragraph::new(status_line).style(Style::default().fg(Color::Gray)), status);
+    }
+
+    fn draw_list(&self, frame: &mut Frame, area: Rect, pane: Pane, items: Vec<ListItem>) {
+        let filter = &self.filters[pane.index()];
+        let mut title = format!(" {} ({}) ", pane.title(), items.len());
+        if !filter.is_empty() {
+            title.push_str(&format!("/{} ", filter));
+        }
+
+        let focused = self.focus == pane;
+        let border = if focused { Style::default().fg(Color::Cyan) } else { Style::default() };
+        let highlight = if focused {
+            Style::default().add_modifier(Modifier::REVERSED)
+        } else {
+            Style::default().add_modifier(Modifier::BOLD)
+        };
+
+        let mut state = ListState::default();
+        if !items.is_empty() {
+            state.select(Some(self.selected[pane.index()]));
+        }
+        let list = List::new(items)
+            .block(Block::default().borders(Borders::ALL).border_style(border).title(title))
+            .highlight_style(highlight);
+        frame.render_stateful_widget(list, area, &mut state);
+    }
+
+    fn draw_detail(&self, frame: &mut Frame, area: Rect) {
+        let block = Block::default().borders(Borders::ALL);
+
+        if let Mode::Edit { column, buffer, cursor } = &self.mode {
+            let target = column.as_deref().unwrap_or("table");
+            let block = block.border_style(Style::default().fg(Color::Yellow))
+                .title(format!(" Edit description of {} (Enter save, Esc cancel) ", target));
+            let inner = block.inner(area);
+            let text: String = buffer.iter().collect();
+            frame.render_widget(Paragraph::new(text).block(block).wrap(Wrap { trim: false }), area);
+
+            let width = inner.width.max(1) as usize;
+            frame.set_cursor_position(Position::new(
+                inner.x + (cursor % width) as u16,
+                inner.y + (cursor / width) as u16,
+            ));
+            return;
+        }
+
+        let mut lines: Vec<Line> = Vec::new();
+        let label = |name: &str, value: String| Line::from(vec![
+            Span::styled(format!("{}: ", name), Style::default().fg(Color::DarkGray)),
+            Span::raw(value),
+        ]);
+        let description = |d: &Option<String>| d.clone().unwrap_or_else(|| "(no description)".to_string());
+
+        match (self.focus, self.current_table()) {
+            (Pane::Columns, Some(table)) if self.current_column().is_some() => {
+                let metadata = &self.tables[table].metadata;
+                if let Some(path) = self.current_column() {
+                    if let Some(column) = metadata.find_column(&path) {
+                        lines.push(Line::from(Span::styled(path.clone(), Style::default().add_modifier(Modifier::BOLD))));
+                        lines.push(label("Type", column.column_type.clone()));
+                        lines.push(label("Mode", column.mode.clone().unwrap_or_else(|| "NULLABLE".to_string())));
+                        lines.push(Line::from(description(&column.description)));
+                    }
+                }
+            }
+            (Pane::Tables | Pane::Columns, Some(table)) => {
+                let entry = &self.tables[table];
+                let path = format!("{}.{}.{}", entry.project_id, entry.dataset_id, entry.table_name);
+                lines.push(Line::from(Span::styled(path, Style::default().add_modifier(Modifier::BOLD))));
+                if let Some(ref table_type) = entry.metadata.table.table_type {
+                    lines.push(label("Type", table_type.clone()));
+                }
+                lines.push(label("Columns", entry.metadata.flatten_columns().len().to_string()));
+                lines.push(Line::from(description(&entry.metadata.table.description)));
+            }
+            _ => {
+                if let Some((project_id, dataset_id)) = self.current_dataset() {
+                    lines.push(Line::from(Span::styled(
+                        format!("{}.{}", project_id, dataset_id),
+                        Style::default().add_modifier(Modifier::BOLD),
+                    )));
+                    lines.push(label("Tables", self.visible_tables().len().to_string()));
+                }
+            }
+        }
+
+        frame.render_widget(Paragraph::new(lines).block(block.title(" Details ")).wrap(Wrap { trim: false }), area);
+    }
+}
+
+fn undocumented_style(undocumented: bool) -> Style {
+    if undocumented {
+        Style::default().fg(Color::Yellow)
+    } else {
+        Style::default()
+    }
+}
+
+/// Run the interactive browser until the user quits
+pub fn run() -> Result<()> {
+    let mut app = App::load()?;
+    let mut terminal = ratatui::init();
+    let result = app.run(&mut terminal);
+    ratatui::restore();
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{ColumnInfo, TableInfo};
+
+    fn entry(dataset: &str, name: &str, columns: &[&str]) -> TableEntry {
+        TableEntry {
+            project_id: "p".to_string(),
+            dataset_id: dataset.to_string(),
+            table_name: name.to_string(),
+            metadata: TableMetadata {
+                table: TableInfo { name: name.to_string(), ..Default::default() },
+                columns: columns.iter()
+                    .map(|c| ColumnInfo { name: c.to_string(), column_type: "STRING".to_string(), ..Default::default() })
+                    .collect(),
+            },
+        }
+    }
+
+    fn press(app: &mut App, keys: &str) {
+        for c in keys.chars() {
+            let code = match c {
+                '\n' => KeyCode::Enter,
+                '\t' => KeyCode::Tab,
+                c => KeyCode::Char(c),
+            };
+            app.handle_key(KeyEvent::new(code, KeyModifiers::NONE));
+        }
+    }
+
+    #[test]
+    fn test_filter_and_navigate() {
+        assert!(fuzzy_score("user_events", "uev").is_some());
+        assert!(fuzzy_score("user_events", "ve_u").is_none());
+        assert!(fuzzy_score("events", "ev") > fuzzy_score("reviews", "ev"));
+
+        let mut app = App::new(vec![
+            entry("analytics", "reviews", &["id"]),
+            entry("analytics", "user_events", &["event_name", "user_id"]),
+            entry("staging", "orders", &["order_id"]),
+        ]);
+        assert_eq!(app.visible_datasets().len(), 2);
+        assert_eq!(app.visible_tables().len(), 2);
+
+        // Filter the tables pane, then move to the columns of the best match
+        press(&mut app, "\t/uev\n");
+        assert_eq!(app.current_table().map(|t| app.tables[t].table_name.as_str()), Some("user_events"));
+        press(&mut app, "\tj");
+        assert_eq!(app.current_column().as_deref(), Some("user_id"));
+
+        // Cancelling an edit leaves the metadata untouched
+        press(&mut app, "enew");
+        app.handle_key(KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE));
+        assert!(matches!(app.mode, Mode::Browse));
+        assert!(app.tables[1].metadata.columns[1].description.is_none());
+
+        // Switching datasets resets the selection further right
+        press(&mut app, "h");
+        app.handle_key(KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE));
+        press(&mut app, "h/st\n");
+        assert_eq!(app.current_table().map(|t| app.tables[t].table_name.as_str()), Some("orders"));
+        assert_eq!(app.selected, [0, 0, 0]);
+    }
+}
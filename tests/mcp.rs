@@ -0,0 +1,75 @@
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+fn setup_data_dir() -> PathBuf {
+    let root = std::env::temp_dir().join(format!("bq-meta-mcp-test-{}", std::process::id()));
+    let dataset_dir = root.join("data").join("my-project").join("analytics");
+    std::fs::create_dir_all(&dataset_dir).unwrap();
+    std::fs::write(dataset_dir.join("orders.yaml"), r#"
+table:
+  name: orders
+  project_id: my-project
+  dataset_id: analytics
+  description: Customer orders
+columns:
+  - name: customer_id
+    type: STRING
+    description: Customer identifier
+  - name: items
+    type: RECORD
+    mode: REPEATED
+    fields:
+      - name: sku
+        type: STRING
+        description: Stock keeping unit
+"#).unwrap();
+    root
+}
+
+#[test]
+fn test_mcp_over_stdio() {
+    let root = setup_data_dir();
+    let mut child = Command::new(env!("CARGO_BIN_EXE_bq-meta"))
+        .arg("mcp")
+        .env("BQ_META_PATH", &root)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    let requests = [
+        r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{"protocolVersion":"2025-03-26","capabilities":{},"clientInfo":{"name":"test","version":"0"}}}"#,
+        r#"{"jsonrpc":"2.0","method":"notifications/initialized"}"#,
+        r#"{"jsonrpc":"2.0","id":2,"method":"tools/call","params":{"name":"list_tables","arguments":{}}}"#,
+        r#"{"jsonrpc":"2.0","id":3,"method":"tools/call","params":{"name":"describe_table","arguments":{"table":"my-project.analytics.orders"}}}"#,
+        r#"{"jsonrpc":"2.0","id":4,"method":"tools/call","params":{"name":"search_columns","arguments":{"pattern":"sku"}}}"#,
+        r#"{"jsonrpc":"2.0","id":5,"method":"tools/call","params":{"name":"describe_table","arguments":{"table":"my-project.analytics.missing"}}}"#,
+    ];
+    let mut stdin = child.stdin.take().unwrap();
+    for request in requests {
+        writeln!(stdin, "{}", request).unwrap();
+    }
+    drop(stdin);
+
+    let output = child.wait_with_output().unwrap();
+    std::fs::remove_dir_all(&root).unwrap();
+    assert!(output.status.success());
+
+    let responses: Vec<serde_json::Value> = String::from_utf8(output.stdout).unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    // The notification gets no response
+    assert_eq!(responses.len(), 5);
+    assert_eq!(responses[0]["result"]["protocolVersion"], "2025-03-26");
+
+    let text = |i: usize| -> serde_json::Value {
+        serde_json::from_str(responses[i]["result"]["content"][0]["text"].as_str().unwrap()).unwrap()
+    };
+    assert_eq!(text(1), serde_json::json!(["my-project.analytics.orders"]));
+    assert_eq!(text(2)["columns"][2]["name"], "items.sku");
+    assert_eq!(text(3)[0]["column"], "items.sku");
+    assert_eq!(text(3)[0]["type"], "STRING");
+    assert_eq!(responses[4]["result"]["isError"], true);
+}